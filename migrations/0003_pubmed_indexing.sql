@@ -0,0 +1,5 @@
+ALTER TABLE docs ADD COLUMN pmcid TEXT;
+ALTER TABLE docs ADD COLUMN mesh_headings TEXT;
+ALTER TABLE docs ADD COLUMN publication_types TEXT;
+ALTER TABLE docs ADD COLUMN keywords TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_docs_pmcid ON docs(pmcid) WHERE pmcid IS NOT NULL AND pmcid <> '';
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS trials (
+    nct_id TEXT PRIMARY KEY,
+    doc_id TEXT,
+    title TEXT NOT NULL,
+    official_title TEXT,
+    phases TEXT,
+    overall_status TEXT,
+    start_date TEXT,
+    completion_date TEXT,
+    enrollment INTEGER,
+    enrollment_type TEXT,
+    conditions TEXT,
+    interventions TEXT,
+    primary_outcomes TEXT,
+    secondary_outcomes TEXT,
+    sponsor TEXT,
+    has_results INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_trials_doc_id ON trials(doc_id);
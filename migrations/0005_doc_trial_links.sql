@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS doc_trial_links (
+    doc_id TEXT NOT NULL,
+    nct_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (doc_id, nct_id, source)
+);
+CREATE INDEX IF NOT EXISTS idx_doc_trial_links_nct ON doc_trial_links(nct_id);
@@ -0,0 +1,2 @@
+ALTER TABLE docs ADD COLUMN oa_locations TEXT;
+ALTER TABLE docs ADD COLUMN license TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE docs ADD COLUMN is_preprint INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE docs ADD COLUMN preprint_doi TEXT;
+CREATE INDEX IF NOT EXISTS idx_docs_preprint_doi ON docs(preprint_doi);
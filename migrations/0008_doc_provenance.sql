@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS doc_provenance (
+    doc_id TEXT NOT NULL,
+    provenance TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (doc_id, provenance)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE docs ADD COLUMN update_status TEXT;
+ALTER TABLE docs ADD COLUMN update_notice TEXT;
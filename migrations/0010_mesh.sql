@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS mesh_descriptors (
+    ui TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    tree_numbers TEXT NOT NULL DEFAULT '[]',
+    entry_terms TEXT NOT NULL DEFAULT '[]'
+);
+
+CREATE TABLE IF NOT EXISTS mesh_tree (
+    tree_number TEXT PRIMARY KEY,
+    ui TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS mesh_terms (
+    term_key TEXT NOT NULL,
+    ui TEXT NOT NULL,
+    PRIMARY KEY (term_key, ui)
+);
+
+CREATE TABLE IF NOT EXISTS mesh_meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS doc_aliases (
+    alias_id TEXT PRIMARY KEY,
+    doc_id TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
@@ -1,7 +1,9 @@
-use crate::domain::evidence::EvidenceLedgerRow;
+use crate::domain::evidence::{EvidenceLedgerRow, RegistryAnchor};
 use crate::domain::figure::FigureIndexRow;
+use crate::domain::trial::TrialRecord;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BriefTakeaway {
@@ -18,6 +20,8 @@ pub struct BriefCitation {
     pub anchor_type: String,
     pub page_number: Option<u32>,
     pub section_heading: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryAnchor>,
     pub anchor_quote: String,
 }
 
@@ -31,6 +35,45 @@ pub struct BriefKeyFigure {
     pub license: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefTrial {
+    pub nct_id: String,
+    pub doc_id: Option<String>,
+    pub title: String,
+    pub phases: Vec<String>,
+    pub overall_status: Option<String>,
+    pub start_date: Option<String>,
+    pub completion_date: Option<String>,
+    pub enrollment: Option<u32>,
+    pub conditions: Vec<String>,
+    pub interventions: Vec<String>,
+    pub primary_outcomes: Vec<String>,
+    pub sponsor: Option<String>,
+    /// Docs that report this trial; empty when no publication has been linked yet.
+    #[serde(default)]
+    pub publications: Vec<String>,
+}
+
+impl From<TrialRecord> for BriefTrial {
+    fn from(t: TrialRecord) -> Self {
+        Self {
+            nct_id: t.nct_id,
+            doc_id: t.doc_id,
+            title: t.title,
+            phases: t.phases,
+            overall_status: t.overall_status,
+            start_date: t.start_date,
+            completion_date: t.completion_date,
+            enrollment: t.enrollment,
+            conditions: t.conditions,
+            interventions: t.interventions.into_iter().map(|i| i.name).collect(),
+            primary_outcomes: t.primary_outcomes.into_iter().map(|o| o.measure).collect(),
+            sponsor: t.sponsor,
+            publications: vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BriefJson {
     pub slug: String,
@@ -39,19 +82,25 @@ pub struct BriefJson {
     pub takeaways: Vec<BriefTakeaway>,
     pub citations: Vec<BriefCitation>,
     pub key_figures: Vec<BriefKeyFigure>,
+    /// Trials tied to the cited docs or the query; selected after the claims are composed.
+    #[serde(default)]
+    pub trials: Vec<BriefTrial>,
 }
 
+/// Claims and figures from `retracted_docs` are left out.
 pub fn compose_brief(
     slug: String,
     query: String,
     ranked_claims: Vec<EvidenceLedgerRow>,
     figures: Vec<FigureIndexRow>,
+    retracted_docs: &HashSet<String>,
     max_takeaways: usize,
     max_figures: usize,
 ) -> BriefJson {
     let claims = ranked_claims
         .into_iter()
         .filter(|c| c.claim_text != "unknown")
+        .filter(|c| !retracted_docs.contains(&c.doc_id))
         .take(max_takeaways)
         .collect::<Vec<_>>();
 
@@ -65,9 +114,11 @@ pub fn compose_brief(
             anchor_type: match claim.anchor_type {
                 crate::domain::evidence::AnchorType::Pdf => "pdf".to_string(),
                 crate::domain::evidence::AnchorType::Xml => "xml".to_string(),
+                crate::domain::evidence::AnchorType::Registry => "registry".to_string(),
             },
             page_number: claim.page_number,
             section_heading: claim.section_heading.clone(),
+            registry: claim.registry.clone(),
             anchor_quote: claim.anchor_quote.clone(),
         })
         .collect::<Vec<_>>();
@@ -82,6 +133,7 @@ pub fn compose_brief(
 
     let key_figures = figures
         .into_iter()
+        .filter(|fig| !retracted_docs.contains(&fig.doc_id))
         .take(max_figures)
         .map(|fig| {
             let provenance = if let Some(page) = fig.page_number {
@@ -113,5 +165,6 @@ pub fn compose_brief(
         takeaways,
         citations,
         key_figures,
+        trials: vec![],
     }
 }
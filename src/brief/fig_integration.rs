@@ -20,7 +20,7 @@ pub fn integrate_figures_and_sources(
     }
 
     let mut ranked_docs = contribution.into_iter().collect::<Vec<_>>();
-    ranked_docs.sort_by(|a, b| b.1.cmp(&a.1));
+    ranked_docs.sort_by_key(|entry| std::cmp::Reverse(entry.1));
 
     let sources_dir = brief_dir.join("sources");
     let figs_dir = brief_dir.join("figures");
@@ -33,11 +33,35 @@ pub fn score_claim(claim: &EvidenceLedgerRow, doc: Option<&DocRecord>, query: &s
     }
 
     if let Some(doc) = doc {
-        if let Some(year) = doc.year {
-            let years_old = (Utc::now().year() - year).max(0) as f64;
-            score += (10.0 - (years_old / 2.0)).max(0.0) * 0.1;
-        }
+        score += publication_type_boost(&doc.publication_types);
+        let query_lower = query.to_lowercase();
+        let mesh_hits = doc
+            .mesh_headings
+            .iter()
+            .filter(|m| query_lower.contains(&m.descriptor.to_lowercase()))
+            .count();
+        score += mesh_hits as f64 * 0.5;
+    }
+
+    if let Some(year) = doc.and_then(|d| d.year) {
+        let years_old = (Utc::now().year() - year).max(0) as f64;
+        score += (10.0 - (years_old / 2.0)).max(0.0) * 0.1;
     }
 
     score
 }
+
+/// Evidence-level boost from PubMed publication types; the strongest type wins.
+fn publication_type_boost(types: &[String]) -> f64 {
+    types
+        .iter()
+        .map(|t| match t.to_lowercase().as_str() {
+            "practice guideline" | "guideline" | "consensus development conference" => 3.0,
+            "meta-analysis" | "systematic review" => 2.5,
+            "randomized controlled trial" => 2.0,
+            "clinical trial" | "multicenter study" | "comparative study" => 1.0,
+            "review" => 0.5,
+            _ => 0.0,
+        })
+        .fold(0.0, f64::max)
+}
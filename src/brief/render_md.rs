@@ -20,6 +20,8 @@ pub fn render_markdown(brief: &BriefJson) -> String {
     for citation in &brief.citations {
         let anchor = if citation.anchor_type == "pdf" {
             format!("page {}", citation.page_number.unwrap_or_default())
+        } else if let Some(reg) = &citation.registry {
+            reg.label()
         } else {
             citation
                 .section_heading
@@ -49,5 +51,49 @@ pub fn render_markdown(brief: &BriefJson) -> String {
         ));
     }
 
+    if !brief.trials.is_empty() {
+        out.push_str("\n## Registered Trials\n\n");
+        for trial in &brief.trials {
+            let phase = if trial.phases.is_empty() {
+                "N/A".to_string()
+            } else {
+                trial.phases.join("/")
+            };
+            out.push_str(&format!(
+                "- `{}` {}\n  - phase: {} status: {} enrollment: {}\n  - dates: {} to {}\n",
+                trial.nct_id,
+                trial.title,
+                phase,
+                trial.overall_status.clone().unwrap_or_else(|| "unknown".to_string()),
+                trial
+                    .enrollment
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "N/A".to_string()),
+                trial.start_date.clone().unwrap_or_else(|| "?".to_string()),
+                trial.completion_date.clone().unwrap_or_else(|| "?".to_string()),
+            ));
+            if !trial.conditions.is_empty() {
+                out.push_str(&format!("  - conditions: {}\n", trial.conditions.join("; ")));
+            }
+            if !trial.interventions.is_empty() {
+                out.push_str(&format!("  - interventions: {}\n", trial.interventions.join("; ")));
+            }
+            if !trial.primary_outcomes.is_empty() {
+                out.push_str(&format!(
+                    "  - primary outcomes: {}\n",
+                    trial.primary_outcomes.join("; ")
+                ));
+            }
+            if trial.publications.is_empty() {
+                out.push_str("  - publications: none linked yet\n");
+            } else {
+                out.push_str(&format!("  - publications: {}\n", trial.publications.join(", ")));
+            }
+            if let Some(sponsor) = &trial.sponsor {
+                out.push_str(&format!("  - sponsor: {sponsor}\n"));
+            }
+        }
+    }
+
     out
 }
@@ -2,7 +2,7 @@ use crate::brief::compose::BriefJson;
 use crate::domain::evidence::{AnchorType, EvidenceLedgerRow};
 use crate::domain::figure::FigureIndexRow;
 use crate::errors::{LitError, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 pub fn validate_brief(brief: &BriefJson, ledger_rows: &[EvidenceLedgerRow]) -> Result<()> {
@@ -41,6 +41,11 @@ pub fn validate_brief(brief: &BriefJson, ledger_rows: &[EvidenceLedgerRow]) -> R
                         && cite.section_heading.is_some()
                         && ledger.section_heading == cite.section_heading
                 }
+                AnchorType::Registry => {
+                    cite.anchor_type == "registry"
+                        && cite.registry.is_some()
+                        && ledger.registry == cite.registry
+                }
             };
             if !anchor_matches {
                 return Err(LitError::Validation(format!(
@@ -60,6 +65,21 @@ pub fn validate_brief(brief: &BriefJson, ledger_rows: &[EvidenceLedgerRow]) -> R
     Ok(())
 }
 
+/// Doc ids the brief cites (claims or key figures) that are in `retracted`, sorted.
+pub fn retracted_citations(brief: &BriefJson, retracted: &HashSet<String>) -> Vec<String> {
+    let mut hits = brief
+        .citations
+        .iter()
+        .map(|c| &c.doc_id)
+        .chain(brief.key_figures.iter().map(|f| &f.doc_id))
+        .filter(|id| retracted.contains(*id))
+        .cloned()
+        .collect::<Vec<_>>();
+    hits.sort();
+    hits.dedup();
+    hits
+}
+
 pub fn validate_brief_figures(brief: &BriefJson, figures_index: &[FigureIndexRow]) -> Result<()> {
     let fig_map = figures_index
         .iter()
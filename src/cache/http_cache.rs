@@ -1,6 +1,9 @@
+use crate::config::CacheConfig;
 use crate::errors::Result;
 use crate::fs::hash::sha256_bytes;
+use crate::net::url::redact_url;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -8,41 +11,89 @@ use std::time::{SystemTime, UNIX_EPOCH};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CacheEnvelope {
     fetched_at: u64,
+    /// Entries written before per-source bookkeeping have no source and fall under "unknown".
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
     body: String,
 }
 
+/// A cached response; stale entries are still returned so they can be revalidated.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fresh: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceCacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+    pub expired: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpCache {
     root: PathBuf,
-    ttl_secs: u64,
+    default_ttl_secs: u64,
+    ttl_secs: HashMap<String, u64>,
 }
 
 impl HttpCache {
-    pub fn new(root: &Path, ttl_secs: u64) -> Result<Self> {
+    pub fn new(root: &Path, cfg: &CacheConfig) -> Result<Self> {
         fs::create_dir_all(root)?;
         Ok(Self {
             root: root.to_path_buf(),
-            ttl_secs,
+            default_ttl_secs: cfg.default_ttl_secs,
+            ttl_secs: cfg.ttl_secs.clone(),
         })
     }
 
-    pub fn get(&self, key: &str) -> Result<Option<String>> {
+    /// Seconds an entry for `source` is served without revalidation; 0 always revalidates.
+    pub fn ttl_for(&self, source: &str) -> u64 {
+        self.ttl_secs
+            .get(source)
+            .copied()
+            .unwrap_or(self.default_ttl_secs)
+    }
+
+    pub fn get(&self, source: &str, key: &str) -> Result<Option<CachedResponse>> {
         let path = self.path_for(key);
         if !path.exists() {
             return Ok(None);
         }
         let raw = fs::read_to_string(path)?;
         let env: CacheEnvelope = serde_json::from_str(&raw)?;
-        let now = now_epoch();
-        if now.saturating_sub(env.fetched_at) > self.ttl_secs {
-            return Ok(None);
-        }
-        Ok(Some(env.body))
+        Ok(Some(CachedResponse {
+            fresh: now_epoch().saturating_sub(env.fetched_at) < self.ttl_for(source),
+            body: env.body,
+            etag: env.etag,
+            last_modified: env.last_modified,
+        }))
     }
 
-    pub fn put(&self, key: &str, body: &str) -> Result<()> {
+    pub fn put(
+        &self,
+        source: &str,
+        url: &str,
+        key: &str,
+        body: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) -> Result<()> {
         let env = CacheEnvelope {
             fetched_at: now_epoch(),
+            source: source.to_string(),
+            url: redact_url(url),
+            etag,
+            last_modified,
             body: body.to_string(),
         };
         let serialized = serde_json::to_string(&env)?;
@@ -50,8 +101,90 @@ impl HttpCache {
         Ok(())
     }
 
+    /// Restarts an entry's TTL after the server confirmed it unchanged (304).
+    pub fn touch(&self, key: &str) -> Result<()> {
+        let path = self.path_for(key);
+        let mut env: CacheEnvelope = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        env.fetched_at = now_epoch();
+        fs::write(path, serde_json::to_string(&env)?)?;
+        Ok(())
+    }
+
+    /// Entry counts and sizes per source, optionally for one source only.
+    pub fn stats(&self, source: Option<&str>) -> Result<BTreeMap<String, SourceCacheStats>> {
+        let mut out: BTreeMap<String, SourceCacheStats> = BTreeMap::new();
+        for (_, env, bytes) in self.entries()? {
+            let name = env
+                .as_ref()
+                .map(|e| source_name(e).to_string())
+                .unwrap_or_else(|| "unreadable".to_string());
+            if source.is_some_and(|s| s != name) {
+                continue;
+            }
+            let stats = out.entry(name).or_default();
+            stats.entries += 1;
+            stats.bytes += bytes;
+            if env.as_ref().is_none_or(|e| self.is_expired(e)) {
+                stats.expired += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Removes expired and unreadable entries; returns (entries removed, bytes freed).
+    pub fn prune(&self, source: Option<&str>) -> Result<(usize, u64)> {
+        self.remove_where(source, |cache, env| env.is_none_or(|e| cache.is_expired(e)))
+    }
+
+    /// Removes every entry, or every entry for `source`.
+    pub fn clear(&self, source: Option<&str>) -> Result<(usize, u64)> {
+        self.remove_where(source, |_, _| true)
+    }
+
+    /// Credentials (`api_key`, `email`, `mailto`) are left out, so setting or rotating a key
+    /// keeps existing entries.
     pub fn key_for(source: &str, url: &str) -> String {
-        sha256_bytes(format!("{source}|{url}").as_bytes())
+        sha256_bytes(format!("{source}|{}", redact_url(url)).as_bytes())
+    }
+
+    fn remove_where(
+        &self,
+        source: Option<&str>,
+        pred: impl Fn(&Self, Option<&CacheEnvelope>) -> bool,
+    ) -> Result<(usize, u64)> {
+        let mut removed = 0usize;
+        let mut freed = 0u64;
+        for (path, env, bytes) in self.entries()? {
+            let name = env.as_ref().map(source_name).unwrap_or("unreadable");
+            if source.is_some_and(|s| s != name) || !pred(self, env.as_ref()) {
+                continue;
+            }
+            fs::remove_file(&path)?;
+            removed += 1;
+            freed += bytes;
+        }
+        Ok((removed, freed))
+    }
+
+    fn is_expired(&self, env: &CacheEnvelope) -> bool {
+        now_epoch().saturating_sub(env.fetched_at) >= self.ttl_for(source_name(env))
+    }
+
+    /// Every cache file with its parsed envelope (`None` when unreadable) and size.
+    fn entries(&self) -> Result<Vec<(PathBuf, Option<CacheEnvelope>, u64)>> {
+        let mut out = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+            let env = fs::read_to_string(&path)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<CacheEnvelope>(&raw).ok());
+            out.push((path, env, bytes));
+        }
+        Ok(out)
     }
 
     fn path_for(&self, key: &str) -> PathBuf {
@@ -59,9 +192,82 @@ impl HttpCache {
     }
 }
 
+fn source_name(env: &CacheEnvelope) -> &str {
+    if env.source.is_empty() {
+        "unknown"
+    } else {
+        &env.source
+    }
+}
+
 fn now_epoch() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::HttpCache;
+    use crate::config::CacheConfig;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn per_source_ttl_prune_and_clear() {
+        let dir = tempdir().expect("tempdir");
+        let cfg = CacheConfig {
+            default_ttl_secs: 3600,
+            ttl_secs: HashMap::from([("clinicaltrials".to_string(), 0)]),
+        };
+        let cache = HttpCache::new(dir.path(), &cfg).expect("cache");
+        let pubmed = HttpCache::key_for("pubmed", "https://a");
+        let trials = HttpCache::key_for("clinicaltrials", "https://b");
+        cache
+            .put(
+                "pubmed",
+                "https://a",
+                &pubmed,
+                "a",
+                Some("\"v1\"".into()),
+                None,
+            )
+            .expect("put");
+        cache
+            .put("clinicaltrials", "https://b", &trials, "b", None, None)
+            .expect("put");
+
+        let hit = cache.get("pubmed", &pubmed).expect("get").expect("hit");
+        assert!(hit.fresh);
+        assert_eq!(hit.etag.as_deref(), Some("\"v1\""));
+        assert!(
+            !cache
+                .get("clinicaltrials", &trials)
+                .expect("get")
+                .expect("hit")
+                .fresh
+        );
+
+        let stats = cache.stats(None).expect("stats");
+        assert_eq!(stats["clinicaltrials"].expired, 1);
+        assert_eq!(stats["pubmed"].expired, 0);
+
+        assert_eq!(cache.prune(None).expect("prune").0, 1);
+        assert_eq!(cache.clear(Some("openalex")).expect("clear").0, 0);
+        assert_eq!(cache.clear(Some("pubmed")).expect("clear").0, 1);
+        assert!(cache.stats(None).expect("stats").is_empty());
+    }
+
+    #[test]
+    fn keys_ignore_credentials() {
+        assert_eq!(
+            HttpCache::key_for("pubmed", "https://e/esearch.fcgi?term=x&api_key=abc"),
+            HttpCache::key_for("pubmed", "https://e/esearch.fcgi?term=x&api_key=def")
+        );
+        assert_ne!(
+            HttpCache::key_for("pubmed", "https://e/esearch.fcgi?term=x"),
+            HttpCache::key_for("pubmed", "https://e/esearch.fcgi?term=y")
+        );
+    }
+}
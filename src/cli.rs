@@ -1,4 +1,5 @@
-use clap::{Args, Parser, Subcommand};
+use crate::query::window::DateWindow;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
@@ -12,6 +13,9 @@ pub struct Cli {
     pub log_level: String,
     #[arg(long, default_value_t = false)]
     pub no_color: bool,
+    /// Never touch the network: serve API calls from the cache regardless of TTL.
+    #[arg(long, global = true, default_value_t = false)]
+    pub offline: bool,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -20,6 +24,11 @@ pub struct Cli {
 pub enum Commands {
     Search(SearchArgs),
     Fetch(FetchArgs),
+    #[command(name = "reconcile-ids")]
+    ReconcileIds(ReconcileIdsArgs),
+    Snowball(SnowballArgs),
+    #[command(name = "check-updates")]
+    CheckUpdates(CheckUpdatesArgs),
     #[command(name = "download-oa")]
     DownloadOa(DownloadOaArgs),
     #[command(name = "ingest-local")]
@@ -30,20 +39,33 @@ pub enum Commands {
     Brief(BriefArgs),
     Qa(QaArgs),
     Run(RunArgs),
+    Sources(SourcesArgs),
+    Docs(DocsArgs),
+    Mesh(MeshArgs),
+    Cache(CacheArgs),
 }
 
 #[derive(Debug, Args, Clone)]
 pub struct SearchArgs {
     #[arg(long)]
     pub query: String,
-    #[arg(long, default_value = "30d")]
-    pub since: String,
+    /// Date window: 30d, 6m, 2y, 2020-01-01, 2019..2023 or 2024-01-01..2024-06-30;
+    /// defaults to `[pipeline].default_since` from config.
+    #[arg(long)]
+    pub since: Option<DateWindow>,
     #[arg(long, default_value_t = 500)]
     pub limit: usize,
-    #[arg(long, value_delimiter = ',', default_value = "pubmed,europepmc,crossref,openalex,clinicaltrials")]
+    /// Comma-separated source names; defaults to `[sources].enabled` from config.
+    #[arg(long, value_delimiter = ',')]
     pub sources: Vec<String>,
     #[arg(long)]
     pub out: Option<PathBuf>,
+    /// Print the parsed query and each source's translation, then exit without searching.
+    #[arg(long, default_value_t = false)]
+    pub explain: bool,
+    /// Expand terms with MeSH entry terms and narrower headings (needs `lit mesh load`).
+    #[arg(long, default_value_t = false)]
+    pub expand_mesh: bool,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -54,6 +76,47 @@ pub struct FetchArgs {
     pub enrich: bool,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct ReconcileIdsArgs {
+    /// Comma-separated doc ids; all docs with a missing DOI/PMID/PMCID when omitted.
+    #[arg(long)]
+    pub doc_ids: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct CheckUpdatesArgs {
+    /// Comma-separated doc ids; all docs when omitted.
+    #[arg(long)]
+    pub doc_ids: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SnowballArgs {
+    /// Comma-separated seed doc ids.
+    #[arg(long)]
+    pub doc_ids: String,
+    #[arg(long, value_enum, default_value_t = SnowballDirection::Both)]
+    pub direction: SnowballDirection,
+    /// Citation hops to follow from each seed.
+    #[arg(long, default_value_t = 1)]
+    pub depth: usize,
+    /// Cap on citing works fetched per work when going forward.
+    #[arg(long, default_value_t = 200)]
+    pub max_citing: usize,
+    /// Cap on works stored by the whole run, across all seeds and levels.
+    #[arg(long, default_value_t = 1000)]
+    pub max_works: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnowballDirection {
+    /// Works the seed references.
+    Back,
+    /// Works that cite the seed.
+    Forward,
+    Both,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct DownloadOaArgs {
     #[arg(long)]
@@ -110,10 +173,80 @@ pub struct QaArgs {
 pub struct RunArgs {
     #[arg(long)]
     pub query: String,
-    #[arg(long, default_value = "30d")]
-    pub since: String,
+    /// Date window: 30d, 6m, 2y, 2020-01-01, 2019..2023 or 2024-01-01..2024-06-30;
+    /// defaults to `[pipeline].default_since` from config.
+    #[arg(long)]
+    pub since: Option<DateWindow>,
     #[arg(long, default_value_t = 500)]
     pub limit: usize,
     #[arg(long, default_value_t = false)]
     pub with_pdf: bool,
+    /// Expand terms with MeSH entry terms and narrower headings (needs `lit mesh load`).
+    #[arg(long, default_value_t = false)]
+    pub expand_mesh: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SourcesArgs {
+    #[command(subcommand)]
+    pub command: SourcesCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum SourcesCommand {
+    List,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct DocsArgs {
+    #[command(subcommand)]
+    pub command: DocsCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum DocsCommand {
+    /// Show a stored doc and the trials it reports; an NCT id shows the trial and its publications.
+    Show { id: String },
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct MeshArgs {
+    #[command(subcommand)]
+    pub command: MeshCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum MeshCommand {
+    /// Load an NLM MeSH descriptor dump (desc20xx.xml or d20xx.bin), replacing any loaded one.
+    Load {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Show the descriptor a term maps to, with its entry terms and narrower headings.
+    Show { term: String },
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum CacheCommand {
+    /// Entries, size and expired entries per source.
+    Stats {
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Delete entries past their source's TTL (and unreadable ones).
+    Prune {
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Delete all entries, or all entries for one source.
+    Clear {
+        #[arg(long)]
+        source: Option<String>,
+    },
 }
@@ -1,5 +1,8 @@
 use crate::errors::{LitError, Result};
+use crate::query::window::DateWindow;
+use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -10,6 +13,16 @@ pub struct AppConfig {
     pub rate_limits: RateLimitConfig,
     pub retry: RetryConfig,
     pub pipeline: PipelineConfig,
+    #[serde(default)]
+    pub sources: SourcesConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub cassette: CassetteConfig,
+    #[serde(default)]
+    pub endpoints: EndpointsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -23,7 +36,13 @@ pub struct DataConfig {
 pub struct NetworkConfig {
     pub user_agent: String,
     pub unpaywall_email: String,
+    /// Contact address sent to NCBI's ID Converter; `unpaywall_email` when empty.
+    #[serde(default)]
+    pub ncbi_email: String,
     pub pubmed_api_key: String,
+    /// Serve API calls only from the HTTP cache and never touch the network.
+    #[serde(default)]
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -34,6 +53,20 @@ pub struct RateLimitConfig {
     pub openalex_per_sec: u32,
     pub unpaywall_per_sec: u32,
     pub clinicaltrials_per_sec: u32,
+    #[serde(default = "default_ncbi_per_sec")]
+    pub idconv_per_sec: u32,
+    #[serde(default = "default_ncbi_per_sec")]
+    pub pmc_oa_per_sec: u32,
+    #[serde(default = "default_biorxiv_per_sec")]
+    pub biorxiv_per_sec: u32,
+}
+
+fn default_ncbi_per_sec() -> u32 {
+    3
+}
+
+fn default_biorxiv_per_sec() -> u32 {
+    2
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -41,17 +74,227 @@ pub struct RetryConfig {
     pub max_attempts: usize,
     pub base_delay_ms: u64,
     pub max_delay_ms: u64,
+    /// Longest `Retry-After` honoured; a server asking for a longer wait fails the request.
+    #[serde(default = "default_max_retry_after_ms")]
+    pub max_retry_after_ms: u64,
+    /// Per-source overrides keyed by source name (`[retry.sources.unpaywall]`).
+    #[serde(default)]
+    pub sources: HashMap<String, RetryOverride>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RetryOverride {
+    pub max_attempts: Option<usize>,
+    pub base_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+    pub max_retry_after_ms: Option<u64>,
+}
+
+fn default_max_retry_after_ms() -> u64 {
+    60_000
+}
+
+impl NetworkConfig {
+    pub fn ncbi_email(&self) -> &str {
+        if self.ncbi_email.trim().is_empty() {
+            &self.unpaywall_email
+        } else {
+            &self.ncbi_email
+        }
+    }
+}
+
+impl RetryConfig {
+    /// The policy for `source`, with its overrides applied.
+    pub fn for_source(&self, source: &str) -> RetryConfig {
+        let over = self.sources.get(source).cloned().unwrap_or_default();
+        RetryConfig {
+            max_attempts: over.max_attempts.unwrap_or(self.max_attempts),
+            base_delay_ms: over.base_delay_ms.unwrap_or(self.base_delay_ms),
+            max_delay_ms: over.max_delay_ms.unwrap_or(self.max_delay_ms),
+            max_retry_after_ms: over.max_retry_after_ms.unwrap_or(self.max_retry_after_ms),
+            sources: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PipelineConfig {
     pub default_limit: usize,
+    /// `--since` when the flag is omitted; any `DateWindow` form.
     pub default_since: String,
     pub strict_qa: bool,
     pub top_k_sources: usize,
     pub max_key_figures: usize,
 }
 
+impl PipelineConfig {
+    /// `default_since` parsed against today, so relative windows stay relative.
+    pub fn default_window(&self) -> Result<DateWindow> {
+        self.default_window_at(Utc::now().date_naive())
+    }
+
+    /// `default_since` with relative forms resolved against `today`.
+    pub fn default_window_at(&self, today: NaiveDate) -> Result<DateWindow> {
+        DateWindow::parse_at(&self.default_since, today).map_err(|e| {
+            LitError::Config(format!("invalid pipeline.default_since '{}': {e}", self.default_since))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourcesConfig {
+    pub enabled: Vec<String>,
+}
+
+impl Default for SourcesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: ["pubmed", "europepmc", "crossref", "openalex", "clinicaltrials"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchConfig {
+    #[serde(default)]
+    pub fail_policy: SourceFailurePolicy,
+}
+
+/// When a failing source should abort `search` instead of being reported in `source_status`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceFailurePolicy {
+    Never,
+    Any,
+    #[default]
+    All,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CacheConfig {
+    #[serde(default = "default_cache_ttl_secs")]
+    pub default_ttl_secs: u64,
+    /// Per-source overrides keyed by source name (`pubmed`, `idconv`, ...).
+    #[serde(default)]
+    pub ttl_secs: HashMap<String, u64>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            default_ttl_secs: default_cache_ttl_secs(),
+            ttl_secs: HashMap::new(),
+        }
+    }
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    60 * 60 * 24
+}
+
+/// Record/replay of API traffic; `LIT_CASSETTE_MODE` and `LIT_CASSETTE_DIR` override the file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CassetteConfig {
+    #[serde(default)]
+    pub mode: CassetteMode,
+    #[serde(default = "default_cassette_dir")]
+    pub dir: PathBuf,
+}
+
+impl Default for CassetteConfig {
+    fn default() -> Self {
+        Self {
+            mode: CassetteMode::default(),
+            dir: default_cassette_dir(),
+        }
+    }
+}
+
+fn default_cassette_dir() -> PathBuf {
+    PathBuf::from("data/cassettes")
+}
+
+/// API base URLs, overridable to point a connector at a mirror or a local mock server.
+/// Each is the prefix the connector appends its own paths to, without a trailing slash.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EndpointsConfig {
+    pub pubmed: String,
+    pub europepmc: String,
+    pub crossref: String,
+    pub openalex: String,
+    pub unpaywall: String,
+    pub clinicaltrials: String,
+    pub idconv: String,
+    pub pmc_oa: String,
+    pub biorxiv: String,
+}
+
+impl Default for EndpointsConfig {
+    fn default() -> Self {
+        Self {
+            pubmed: "https://eutils.ncbi.nlm.nih.gov/entrez/eutils".to_string(),
+            europepmc: "https://www.ebi.ac.uk/europepmc/webservices/rest".to_string(),
+            crossref: "https://api.crossref.org".to_string(),
+            openalex: "https://api.openalex.org".to_string(),
+            unpaywall: "https://api.unpaywall.org/v2".to_string(),
+            clinicaltrials: "https://clinicaltrials.gov/api/v2".to_string(),
+            idconv: "https://pmc.ncbi.nlm.nih.gov/tools/idconv/api/v1/articles".to_string(),
+            pmc_oa: "https://www.ncbi.nlm.nih.gov/pmc/utils/oa/oa.fcgi".to_string(),
+            biorxiv: "https://api.biorxiv.org".to_string(),
+        }
+    }
+}
+
+impl EndpointsConfig {
+    fn trim_trailing_slashes(&mut self) {
+        for url in [
+            &mut self.pubmed,
+            &mut self.europepmc,
+            &mut self.crossref,
+            &mut self.openalex,
+            &mut self.unpaywall,
+            &mut self.clinicaltrials,
+            &mut self.idconv,
+            &mut self.pmc_oa,
+            &mut self.biorxiv,
+        ] {
+            let trimmed = url.trim_end_matches('/').len();
+            url.truncate(trimmed);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CassetteMode {
+    #[default]
+    Off,
+    /// Go live and save every response.
+    Record,
+    /// Serve saved responses only; unrecorded requests fail.
+    Replay,
+}
+
+impl std::str::FromStr for CassetteMode {
+    type Err = LitError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "" => Ok(Self::Off),
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            other => Err(LitError::Config(format!(
+                "unknown cassette mode '{other}' (expected off, record or replay)"
+            ))),
+        }
+    }
+}
+
 impl AppConfig {
     pub fn load(path: Option<&Path>, data_dir: Option<&Path>) -> Result<Self> {
         let default_toml = include_str!("../config/default.toml");
@@ -69,18 +312,32 @@ impl AppConfig {
             cfg.data.root = dir.to_path_buf();
             cfg.data.sqlite_path = dir.join("lit.db");
             cfg.data.cache_dir = dir.join("cache");
+            cfg.cassette.dir = dir.join("cassettes");
         }
 
-        if cfg.network.unpaywall_email.is_empty() {
-            if let Ok(v) = std::env::var("UNPAYWALL_EMAIL") {
-                cfg.network.unpaywall_email = v;
-            }
+        if cfg.network.unpaywall_email.is_empty()
+            && let Ok(v) = std::env::var("UNPAYWALL_EMAIL")
+        {
+            cfg.network.unpaywall_email = v;
+        }
+        if cfg.network.ncbi_email.is_empty()
+            && let Ok(v) = std::env::var("NCBI_EMAIL")
+        {
+            cfg.network.ncbi_email = v;
+        }
+        if let Ok(v) = std::env::var("LIT_CASSETTE_MODE") {
+            cfg.cassette.mode = v.parse()?;
         }
-        if cfg.network.pubmed_api_key.is_empty() {
-            if let Ok(v) = std::env::var("PUBMED_API_KEY") {
-                cfg.network.pubmed_api_key = v;
-            }
+        if let Ok(v) = std::env::var("LIT_CASSETTE_DIR") {
+            cfg.cassette.dir = PathBuf::from(v);
         }
+        if cfg.network.pubmed_api_key.is_empty()
+            && let Ok(v) = std::env::var("PUBMED_API_KEY")
+        {
+            cfg.network.pubmed_api_key = v;
+        }
+        cfg.endpoints.trim_trailing_slashes();
+        cfg.pipeline.default_window()?;
 
         Ok(cfg)
     }
@@ -96,3 +353,23 @@ impl AppConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AppConfig;
+    use crate::errors::LitError;
+
+    #[test]
+    fn default_since_is_validated_and_parsed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cfg = AppConfig::load(None, Some(dir.path())).expect("config");
+        assert!(cfg.pipeline.default_window().expect("window").from.is_some());
+
+        let custom = dir.path().join("lit.toml");
+        let bundled = include_str!("../config/default.toml");
+        let invalid = bundled.replace("default_since = \"30d\"", "default_since = \"soon\"");
+        std::fs::write(&custom, invalid).expect("write");
+        let err = AppConfig::load(Some(&custom), None).expect_err("invalid default_since");
+        assert!(matches!(err, LitError::Config(_)));
+    }
+}
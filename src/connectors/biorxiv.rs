@@ -0,0 +1,307 @@
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
+use crate::connectors::ApiClient;
+use crate::domain::doc::PreprintInfo;
+use crate::errors::Result;
+use crate::query::ast::QueryNode;
+use crate::query::window::DateWindow;
+use crate::types::SearchResult;
+use chrono::{Duration, NaiveDate};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The details endpoint returns 100 records per cursor step.
+const PAGE_SIZE: usize = 100;
+/// Listing is by date, not by query, so cap how much of a window is scanned per search.
+const MAX_SCANNED: usize = 5000;
+/// Window used when `--since` gives no start date.
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+/// bioRxiv and medRxiv share one API; each server is its own source.
+pub struct PreprintConnector {
+    server: &'static str,
+}
+
+impl PreprintConnector {
+    pub fn biorxiv() -> Self {
+        Self { server: "biorxiv" }
+    }
+
+    pub fn medrxiv() -> Self {
+        Self { server: "medrxiv" }
+    }
+}
+
+impl Connector for PreprintConnector {
+    fn name(&self) -> &'static str {
+        self.server
+    }
+
+    fn description(&self) -> &'static str {
+        match self.server {
+            "medrxiv" => "medRxiv preprints (date-windowed listing, matched locally)",
+            _ => "bioRxiv preprints (date-windowed listing, matched locally)",
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: true,
+        }
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(search(
+            client,
+            self.server,
+            &req.parsed,
+            req.limit,
+            &req.since,
+            req.today,
+        ))
+    }
+}
+
+/// Walks the server's listing for the window and keeps preprints whose title or abstract
+/// matches `query`. Only the latest version of each preprint is returned.
+pub async fn search(
+    client: &ApiClient,
+    server: &str,
+    query: &QueryNode,
+    limit: usize,
+    since: &DateWindow,
+    today: NaiveDate,
+) -> Result<SourceBatch> {
+    let (from, to, narrowed) = scan_window(since, today);
+    let mut batch = SourceBatch {
+        since_applied: narrowed.is_none(),
+        warnings: narrowed.into_iter().collect(),
+        ..SourceBatch::default()
+    };
+    let mut latest: HashMap<String, usize> = HashMap::new();
+    let mut cursor = 0usize;
+
+    while batch.results.len() < limit && cursor < MAX_SCANNED {
+        let url = format!(
+            "{}/details/{server}/{}/{}/{cursor}",
+            client.endpoints().biorxiv,
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d")
+        );
+        let payload: Value = match client.get_json_cached("biorxiv", &url).await {
+            Ok(v) => v,
+            Err(err) => {
+                batch.record_page_error(err, &format!("page at cursor {cursor}"))?;
+                break;
+            }
+        };
+        let total = payload["messages"][0]["total"]
+            .as_u64()
+            .or_else(|| payload["messages"][0]["total"].as_str()?.parse().ok());
+
+        let collection = payload["collection"].as_array().cloned().unwrap_or_default();
+        if collection.is_empty() {
+            break;
+        }
+        for item in &collection {
+            if !matches_query(query, item) {
+                continue;
+            }
+            let Some(result) = parse_item(server, item) else {
+                continue;
+            };
+            let key = result.preprint.as_ref().map(|p| p.doi.clone()).unwrap_or_default();
+            match latest.get(&key) {
+                Some(&idx) => {
+                    if version_of(&result) >= version_of(&batch.results[idx]) {
+                        batch.results[idx] = result;
+                    }
+                }
+                None => {
+                    latest.insert(key, batch.results.len());
+                    batch.results.push(result);
+                }
+            }
+        }
+
+        cursor += collection.len();
+        if collection.len() < PAGE_SIZE || total.is_some_and(|t| cursor as u64 >= t) {
+            break;
+        }
+    }
+    if cursor >= MAX_SCANNED {
+        batch.warnings.push(format!(
+            "listing capped after {cursor} preprints; narrow --since to scan the full window"
+        ));
+    }
+
+    batch.results.truncate(limit);
+    Ok(batch)
+}
+
+/// The listing needs both ends of the window. Without a start date only the last
+/// `DEFAULT_WINDOW_DAYS` before the end are scanned, and the returned note says so.
+fn scan_window(since: &DateWindow, today: NaiveDate) -> (NaiveDate, NaiveDate, Option<String>) {
+    let to = since.to.unwrap_or(today);
+    match since.from {
+        Some(from) => (from, to, None),
+        None => {
+            let from = to - Duration::days(DEFAULT_WINDOW_DAYS);
+            let note = format!(
+                "--since {since} has no start date; scanned only {from}..{to} \
+                 (the last {DEFAULT_WINDOW_DAYS} days of the window)"
+            );
+            (from, to, Some(note))
+        }
+    }
+}
+
+fn version_of(result: &SearchResult) -> u32 {
+    result
+        .preprint
+        .as_ref()
+        .and_then(|p| p.version)
+        .unwrap_or_default()
+}
+
+pub fn parse_item(server: &str, item: &Value) -> Option<SearchResult> {
+    let doi = item["doi"].as_str().map(str::trim).filter(|d| !d.is_empty())?;
+    let version = item["version"]
+        .as_str()
+        .and_then(|v| v.parse::<u32>().ok())
+        .or_else(|| item["version"].as_u64().map(|v| v as u32));
+    let published_doi = item["published"]
+        .as_str()
+        .map(str::trim)
+        .filter(|p| !p.is_empty() && !p.eq_ignore_ascii_case("NA"))
+        .map(ToString::to_string);
+    let landing = format!(
+        "https://www.{server}.org/content/{doi}v{}",
+        version.unwrap_or(1)
+    );
+    let pdf_url = format!("{landing}.full.pdf");
+    let preprint = PreprintInfo {
+        server: server.to_string(),
+        doi: doi.to_string(),
+        version,
+        published_doi: published_doi.clone(),
+        pdf_url: Some(pdf_url.clone()),
+        jats_url: str_field(item, "jatsxml"),
+        license: str_field(item, "license").map(|l| l.replace('_', "-")),
+    };
+
+    Some(SearchResult {
+        source: server.to_string(),
+        doi: Some(published_doi.unwrap_or_else(|| doi.to_string())),
+        pmid: None,
+        pmcid: None,
+        title: str_field(item, "title").unwrap_or_else(|| "Untitled".to_string()),
+        journal: None,
+        year: item["date"]
+            .as_str()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse::<i32>().ok()),
+        authors: item["authors"]
+            .as_str()
+            .map(|v| {
+                v.split(';')
+                    .map(|x| x.trim().to_string())
+                    .filter(|x| !x.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        abstract_text: str_field(item, "abstract"),
+        mesh_headings: vec![],
+        publication_types: vec!["Preprint".to_string()],
+        keywords: str_field(item, "category").into_iter().collect(),
+        oa_url: Some(pdf_url),
+        epmc_id: None,
+        url: Some(landing),
+        trial: None,
+        trial_ids: vec![],
+        preprint: Some(preprint),
+        sources: vec![],
+        field_sources: Default::default(),
+        fused_score: None,
+    })
+}
+
+fn str_field(item: &Value, key: &str) -> Option<String> {
+    item[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+/// The API has no search, so the query is evaluated against title and abstract here.
+fn matches_query(query: &QueryNode, item: &Value) -> bool {
+    query.matches(
+        item["title"].as_str().unwrap_or_default(),
+        item["abstract"].as_str().unwrap_or_default(),
+        &[],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches_query, parse_item, scan_window};
+    use crate::query::ast::parse_query;
+    use crate::query::window::DateWindow;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[test]
+    fn parses_preprint_and_links_published_version() {
+        let item = json!({
+            "doi": "10.1101/2023.01.02.23284101",
+            "title": "Tranexamic acid in chronic subdural hematoma",
+            "authors": "Smith, J.; Doe, A.",
+            "date": "2023-01-05",
+            "version": "2",
+            "license": "cc_by_nc_nd",
+            "category": "neurology",
+            "jatsxml": "https://www.medrxiv.org/content/early/2023/01/05/2023.01.02.23284101.source.xml",
+            "abstract": "We pooled trials of tranexamic acid.",
+            "published": "10.1001/jamaneurol.2023.1234",
+            "server": "medRxiv"
+        });
+        let result = parse_item("medrxiv", &item).expect("result");
+        assert_eq!(result.doi.as_deref(), Some("10.1001/jamaneurol.2023.1234"));
+        assert_eq!(result.authors, vec!["Smith, J.", "Doe, A."]);
+        let preprint = result.preprint.expect("preprint");
+        assert_eq!(preprint.doi, "10.1101/2023.01.02.23284101");
+        assert_eq!(preprint.version, Some(2));
+        assert_eq!(preprint.license.as_deref(), Some("cc-by-nc-nd"));
+        assert_eq!(
+            preprint.pdf_url.as_deref(),
+            Some("https://www.medrxiv.org/content/10.1101/2023.01.02.23284101v2.full.pdf")
+        );
+
+        let query = parse_query("(tranexamic AND title:subdural) OR craniotomy").expect("query");
+        assert!(matches_query(&query, &item));
+        assert!(!matches_query(&parse_query("glioma").expect("query"), &item));
+    }
+
+    #[test]
+    fn windows_without_a_start_scan_only_their_last_days() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+
+        let bounded = DateWindow::parse_at("30d", today).unwrap();
+        assert_eq!(scan_window(&bounded, today), (date("2024-05-31"), today, None));
+
+        let all = DateWindow::parse_at("all", today).unwrap();
+        let (from, to, note) = scan_window(&all, today);
+        assert_eq!((from, to), (date("2024-05-31"), today));
+        assert!(note.expect("note").contains("no start date"));
+
+        let until = DateWindow::parse_at("..2020", today).unwrap();
+        let (from, to, note) = scan_window(&until, today);
+        assert_eq!((from, to), (date("2020-12-01"), date("2020-12-31")));
+        assert!(note.is_some());
+    }
+}
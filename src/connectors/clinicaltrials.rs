@@ -1,42 +1,296 @@
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
 use crate::connectors::ApiClient;
+use crate::domain::trial::{TrialIntervention, TrialOutcome, TrialRecord};
 use crate::errors::Result;
+use crate::query::render::Dialect;
+use crate::query::window::DateWindow;
 use crate::types::SearchResult;
+use chrono::Utc;
 use serde_json::Value;
 
-pub async fn search(client: &ApiClient, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-    let url = format!(
-        "https://clinicaltrials.gov/api/v2/studies?query.term={}&pageSize={}",
-        urlencoding::encode(query),
-        limit.min(100)
-    );
-    let payload: Value = client.get_json_cached("clinicaltrials", &url).await?;
-    let studies = payload["studies"].as_array().cloned().unwrap_or_default();
-    let mut out = Vec::new();
+pub struct ClinicalTrialsConnector;
+
+impl Connector for ClinicalTrialsConnector {
+    fn name(&self) -> &'static str {
+        "clinicaltrials"
+    }
+
+    fn description(&self) -> &'static str {
+        "ClinicalTrials.gov v2 studies API"
+    }
 
-    for st in studies {
-        let nct = st["protocolSection"]["identificationModule"]["nctId"]
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: false,
+        }
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::ClinicalTrials
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(search(client, &req.query, req.limit, &req.since))
+    }
+}
+
+pub async fn search(
+    client: &ApiClient,
+    query: &str,
+    limit: usize,
+    since: &DateWindow,
+) -> Result<SourceBatch> {
+    let filter = window_filter(since);
+    let page_size = limit.clamp(1, 1000);
+    let mut batch = SourceBatch {
+        since_applied: !filter.is_empty(),
+        ..SourceBatch::default()
+    };
+    let mut page_token: Option<String> = None;
+
+    while batch.results.len() < limit {
+        let token_param = page_token
+            .as_ref()
+            .map(|t| format!("&pageToken={}", urlencoding::encode(t)))
+            .unwrap_or_default();
+        let url = format!(
+            "{}/studies?query.term={}&pageSize={}&countTotal=true{}{}",
+            client.endpoints().clinicaltrials,
+            urlencoding::encode(query),
+            page_size,
+            filter,
+            token_param
+        );
+        let payload: Value = match client.get_json_cached("clinicaltrials", &url).await {
+            Ok(v) => v,
+            Err(err) => {
+                let at = page_token.as_deref().unwrap_or("first page");
+                batch.record_page_error(err, &format!("page at token {at}"))?;
+                break;
+            }
+        };
+        batch.total_available = payload["totalCount"].as_u64().or(batch.total_available);
+
+        let studies = payload["studies"].as_array().cloned().unwrap_or_default();
+        if studies.is_empty() {
+            break;
+        }
+        batch.results.extend(studies.iter().map(study_to_result));
+
+        match payload["nextPageToken"].as_str() {
+            Some(next) => page_token = Some(next.to_string()),
+            None => break,
+        }
+    }
+
+    batch.results.truncate(limit);
+    Ok(batch)
+}
+
+fn study_to_result(study: &Value) -> SearchResult {
+    let trial = parse_study(study);
+    SearchResult {
+        source: "clinicaltrials".to_string(),
+        doi: None,
+        pmid: None,
+        pmcid: None,
+        title: trial.title.clone(),
+        journal: Some("ClinicalTrials.gov".to_string()),
+        year: trial.start_year(),
+        authors: trial.sponsor.clone().into_iter().collect(),
+        abstract_text: study["protocolSection"]["descriptionModule"]["briefSummary"]
             .as_str()
-            .unwrap_or("unknown");
-        let title = st["protocolSection"]["identificationModule"]["briefTitle"]
+            .map(ToString::to_string),
+        mesh_headings: vec![],
+        publication_types: vec![],
+        keywords: trial.conditions.clone(),
+        oa_url: None,
+        epmc_id: None,
+        url: Some(format!("https://clinicaltrials.gov/study/{}", trial.nct_id)),
+        trial: Some(trial),
+        trial_ids: vec![],
+        preprint: None,
+        sources: vec![],
+        field_sources: Default::default(),
+        fused_score: None,
+    }
+}
+
+pub fn parse_study(study: &Value) -> TrialRecord {
+    let protocol = &study["protocolSection"];
+    let ident = &protocol["identificationModule"];
+    let status = &protocol["statusModule"];
+    let design = &protocol["designModule"];
+
+    TrialRecord {
+        nct_id: ident["nctId"].as_str().unwrap_or("unknown").to_string(),
+        doc_id: None,
+        title: ident["briefTitle"]
             .as_str()
             .unwrap_or("Clinical trial")
-            .to_string();
-        out.push(SearchResult {
-            source: "clinicaltrials".to_string(),
-            doi: None,
-            pmid: None,
-            title,
-            journal: Some("ClinicalTrials.gov".to_string()),
-            year: None,
-            authors: vec![],
-            abstract_text: st["protocolSection"]["descriptionModule"]["briefSummary"]
-                .as_str()
-                .map(ToString::to_string),
-            oa_url: None,
-            epmc_id: None,
-            url: Some(format!("https://clinicaltrials.gov/study/{nct}")),
-        });
+            .to_string(),
+        official_title: str_field(&ident["officialTitle"]),
+        phases: str_list(&design["phases"]),
+        overall_status: str_field(&status["overallStatus"]),
+        start_date: str_field(&status["startDateStruct"]["date"]),
+        completion_date: str_field(&status["completionDateStruct"]["date"]),
+        enrollment: design["enrollmentInfo"]["count"]
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok()),
+        enrollment_type: str_field(&design["enrollmentInfo"]["type"]),
+        conditions: str_list(&protocol["conditionsModule"]["conditions"]),
+        interventions: protocol["armsInterventionsModule"]["interventions"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|i| {
+                Some(TrialIntervention {
+                    kind: str_field(&i["type"]),
+                    name: i["name"].as_str()?.to_string(),
+                })
+            })
+            .collect(),
+        primary_outcomes: outcomes(&protocol["outcomesModule"]["primaryOutcomes"]),
+        secondary_outcomes: outcomes(&protocol["outcomesModule"]["secondaryOutcomes"]),
+        sponsor: str_field(&protocol["sponsorCollaboratorsModule"]["leadSponsor"]["name"]),
+        has_results: study["hasResults"].as_bool().unwrap_or(false),
+        updated_at: Utc::now(),
     }
+}
+
+fn outcomes(raw: &Value) -> Vec<TrialOutcome> {
+    raw.as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|o| {
+            Some(TrialOutcome {
+                measure: o["measure"].as_str()?.to_string(),
+                time_frame: str_field(&o["timeFrame"]),
+                description: str_field(&o["description"]),
+            })
+        })
+        .collect()
+}
+
+fn str_field(v: &Value) -> Option<String> {
+    v.as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+fn str_list(v: &Value) -> Vec<String> {
+    v.as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|x| x.as_str().map(ToString::to_string))
+        .collect()
+}
 
-    Ok(out)
+/// Full study record, including `resultsSection` when results are posted.
+pub async fn get_study(client: &ApiClient, nct_id: &str) -> Result<Value> {
+    let url = format!(
+        "{}/studies/{}",
+        client.endpoints().clinicaltrials,
+        urlencoding::encode(nct_id)
+    );
+    client.get_json_cached("clinicaltrials", &url).await
+}
+
+/// Trials are windowed on first posting, the registry analogue of a publication date.
+fn window_filter(window: &DateWindow) -> String {
+    if window.is_unbounded() {
+        return String::new();
+    }
+    let from = window
+        .from
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "MIN".to_string());
+    let to = window
+        .to
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "MAX".to_string());
+    let expr = format!("AREA[StudyFirstPostDate]RANGE[{from},{to}]");
+    format!("&filter.advanced={}", urlencoding::encode(&expr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_study, study_to_result, window_filter};
+    use crate::query::window::DateWindow;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[test]
+    fn renders_date_windows_as_first_post_ranges() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let window = |raw: &str| {
+            let filter = window_filter(&DateWindow::parse_at(raw, today).unwrap());
+            urlencoding::decode(&filter).unwrap().into_owned()
+        };
+        assert_eq!(
+            window("30d"),
+            "&filter.advanced=AREA[StudyFirstPostDate]RANGE[2024-05-31,MAX]"
+        );
+        assert_eq!(
+            window("..2010"),
+            "&filter.advanced=AREA[StudyFirstPostDate]RANGE[MIN,2010-12-31]"
+        );
+    }
+
+    #[test]
+    fn parses_protocol_sections() {
+        let study = json!({
+            "hasResults": true,
+            "protocolSection": {
+                "identificationModule": {
+                    "nctId": "NCT01234567",
+                    "briefTitle": "Tranexamic acid in chronic subdural hematoma",
+                    "officialTitle": "A Randomized Trial of TXA for cSDH"
+                },
+                "statusModule": {
+                    "overallStatus": "COMPLETED",
+                    "startDateStruct": {"date": "2019-03"},
+                    "completionDateStruct": {"date": "2022-11-30"}
+                },
+                "designModule": {
+                    "phases": ["PHASE2", "PHASE3"],
+                    "enrollmentInfo": {"count": 240, "type": "ACTUAL"}
+                },
+                "conditionsModule": {"conditions": ["Chronic Subdural Hematoma"]},
+                "armsInterventionsModule": {
+                    "interventions": [{"type": "DRUG", "name": "Tranexamic acid"}]
+                },
+                "outcomesModule": {
+                    "primaryOutcomes": [{"measure": "Recurrence", "timeFrame": "6 months"}],
+                    "secondaryOutcomes": [{"measure": "mRS"}]
+                },
+                "sponsorCollaboratorsModule": {"leadSponsor": {"name": "Example University"}},
+                "descriptionModule": {"briefSummary": "Summary."}
+            }
+        });
+
+        let trial = parse_study(&study);
+        assert_eq!(trial.nct_id, "NCT01234567");
+        assert_eq!(trial.phases, vec!["PHASE2", "PHASE3"]);
+        assert_eq!(trial.overall_status.as_deref(), Some("COMPLETED"));
+        assert_eq!(trial.enrollment, Some(240));
+        assert_eq!(trial.interventions[0].kind.as_deref(), Some("DRUG"));
+        assert_eq!(trial.primary_outcomes[0].time_frame.as_deref(), Some("6 months"));
+        assert_eq!(trial.secondary_outcomes[0].measure, "mRS");
+        assert_eq!(trial.sponsor.as_deref(), Some("Example University"));
+        assert!(trial.has_results);
+
+        let result = study_to_result(&study);
+        assert_eq!(result.year, Some(2019));
+        assert_eq!(result.abstract_text.as_deref(), Some("Summary."));
+        assert!(result.trial.is_some());
+    }
 }
@@ -1,15 +1,88 @@
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
 use crate::connectors::ApiClient;
+use crate::domain::doc::UpdateStatus;
 use crate::errors::Result;
+use crate::query::window::DateWindow;
 use crate::types::SearchResult;
 use serde_json::Value;
 
-pub async fn search(client: &ApiClient, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-    let url = format!(
-        "https://api.crossref.org/works?query.bibliographic={}&rows={}",
-        urlencoding::encode(query),
-        limit.min(200)
-    );
-    let payload: Value = client.get_json_cached("crossref", &url).await?;
+pub struct CrossrefConnector;
+
+impl Connector for CrossrefConnector {
+    fn name(&self) -> &'static str {
+        "crossref"
+    }
+
+    fn description(&self) -> &'static str {
+        "Crossref works API"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: false,
+        }
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(search(client, &req.query, req.limit, &req.since))
+    }
+}
+
+pub async fn search(
+    client: &ApiClient,
+    query: &str,
+    limit: usize,
+    since: &DateWindow,
+) -> Result<SourceBatch> {
+    let rows = limit.clamp(1, 1000);
+    let filter = window_filter(since);
+    let mut batch = SourceBatch {
+        since_applied: !filter.is_empty(),
+        ..SourceBatch::default()
+    };
+    let mut cursor = "*".to_string();
+
+    while batch.results.len() < limit {
+        let url = format!(
+            "{}/works?query.bibliographic={}&rows={}&cursor={}{}",
+            client.endpoints().crossref,
+            urlencoding::encode(query),
+            rows,
+            urlencoding::encode(&cursor),
+            filter
+        );
+        let payload: Value = match client.get_json_cached("crossref", &url).await {
+            Ok(v) => v,
+            Err(err) => {
+                batch.record_page_error(err, &format!("page at cursor={cursor}"))?;
+                break;
+            }
+        };
+        batch.total_available = payload["message"]["total-results"]
+            .as_u64()
+            .or(batch.total_available);
+
+        let page = parse_page(&payload);
+        let next = payload["message"]["next-cursor"].as_str().map(ToString::to_string);
+        if page.is_empty() {
+            break;
+        }
+        batch.results.extend(page);
+        match next {
+            Some(next) if next != cursor => cursor = next,
+            _ => break,
+        }
+    }
+
+    batch.results.truncate(limit);
+    Ok(batch)
+}
+
+fn parse_page(payload: &Value) -> Vec<SearchResult> {
     let items = payload["message"]["items"]
         .as_array()
         .cloned()
@@ -56,16 +129,116 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize) -> Result<Vec
             source: "crossref".to_string(),
             doi,
             pmid: None,
+            pmcid: None,
             title,
             journal,
             year,
             authors,
             abstract_text: item["abstract"].as_str().map(ToString::to_string),
+            mesh_headings: vec![],
+            publication_types: vec![],
+            keywords: vec![],
             oa_url: None,
             epmc_id: None,
             url: item["URL"].as_str().map(ToString::to_string),
+            trial: None,
+            trial_ids: vec![],
+            preprint: None,
+            sources: vec![],
+            field_sources: Default::default(),
+            fused_score: None,
         });
     }
 
-    Ok(out)
+    out
+}
+
+/// Notices (retractions, corrections, expressions of concern) whose `update-to` names `doi`,
+/// as `(status, notice DOI)`.
+pub async fn updates_for(client: &ApiClient, doi: &str) -> Result<Vec<(UpdateStatus, String)>> {
+    let url = format!(
+        "{}/works?filter=updates:{}&rows=50",
+        client.endpoints().crossref,
+        urlencoding::encode(doi)
+    );
+    let payload: Value = client.get_json_cached("crossref", &url).await?;
+    Ok(parse_updates(&payload, doi))
+}
+
+pub fn parse_updates(payload: &Value, doi: &str) -> Vec<(UpdateStatus, String)> {
+    let mut out = Vec::new();
+    for notice in payload["message"]["items"].as_array().into_iter().flatten() {
+        let notice_doi = notice["DOI"].as_str().unwrap_or_default().to_lowercase();
+        for update in notice["update-to"].as_array().into_iter().flatten() {
+            let targets_doc = update["DOI"]
+                .as_str()
+                .is_some_and(|d| d.eq_ignore_ascii_case(doi));
+            if let Some(status) = update["type"]
+                .as_str()
+                .and_then(UpdateStatus::from_crossref_type)
+                .filter(|_| targets_doc)
+            {
+                out.push((status, notice_doi.clone()));
+            }
+        }
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn window_filter(window: &DateWindow) -> String {
+    let mut parts = Vec::new();
+    if let Some(from) = window.from {
+        parts.push(format!("from-pub-date:{}", from.format("%Y-%m-%d")));
+    }
+    if let Some(to) = window.to {
+        parts.push(format!("until-pub-date:{}", to.format("%Y-%m-%d")));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("&filter={}", parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_updates, window_filter};
+    use crate::domain::doc::UpdateStatus;
+    use crate::query::window::DateWindow;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[test]
+    fn renders_date_windows_as_pub_date_filters() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let window = |raw: &str| window_filter(&DateWindow::parse_at(raw, today).unwrap());
+        assert_eq!(window("30d"), "&filter=from-pub-date:2024-05-31");
+        assert_eq!(
+            window("2019..2023"),
+            "&filter=from-pub-date:2019-01-01,until-pub-date:2023-12-31"
+        );
+    }
+
+    #[test]
+    fn reads_update_to_notices_for_the_doi() {
+        let payload = json!({"message": {"items": [
+            {"DOI": "10.1000/RETRACTION", "update-to": [
+                {"DOI": "10.1000/orig", "type": "retraction", "label": "Retraction"}
+            ]},
+            {"DOI": "10.1000/erratum", "update-to": [
+                {"DOI": "10.1000/ORIG", "type": "correction"},
+                {"DOI": "10.1000/other", "type": "retraction"}
+            ]}
+        ]}});
+        let updates = parse_updates(&payload, "10.1000/orig");
+        assert_eq!(
+            updates,
+            vec![
+                (UpdateStatus::Retracted, "10.1000/retraction".to_string()),
+                (UpdateStatus::Corrected, "10.1000/erratum".to_string()),
+            ]
+        );
+    }
 }
@@ -1,20 +1,105 @@
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
 use crate::connectors::ApiClient;
 use crate::errors::Result;
+use crate::query::render::Dialect;
+use crate::query::window::{DateWindow, end_or_today};
 use crate::types::SearchResult;
+use chrono::NaiveDate;
 use serde_json::Value;
 
-pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str) -> Result<Vec<SearchResult>> {
-    let q = if since.ends_with('d') {
-        format!("{} FIRST_PDATE:[NOW-{} TO NOW]", query, since)
-    } else {
+/// Europe PMC's public REST root, used for every link that gets stored.
+pub const CANONICAL_BASE: &str = "https://www.ebi.ac.uk/europepmc/webservices/rest";
+
+pub struct EuropePmcConnector;
+
+impl Connector for EuropePmcConnector {
+    fn name(&self) -> &'static str {
+        "europepmc"
+    }
+
+    fn description(&self) -> &'static str {
+        "Europe PMC REST search"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: true,
+        }
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::EuropePmc
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(search(client, &req.query, req.limit, &req.since, req.today))
+    }
+}
+
+pub async fn search(
+    client: &ApiClient,
+    query: &str,
+    limit: usize,
+    since: &DateWindow,
+    today: NaiveDate,
+) -> Result<SourceBatch> {
+    let q = if since.is_unbounded() {
         query.to_string()
+    } else {
+        let from = since
+            .from
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "1800-01-01".to_string());
+        let to = end_or_today(since, today).format("%Y-%m-%d");
+        format!("({query}) AND FIRST_PDATE:[{from} TO {to}]")
+    };
+    let page_size = limit.clamp(1, 1000);
+    let mut batch = SourceBatch {
+        since_applied: !since.is_unbounded(),
+        ..SourceBatch::default()
     };
-    let url = format!(
-        "https://www.ebi.ac.uk/europepmc/webservices/rest/search?query={}&format=json&pageSize={}",
-        urlencoding::encode(&q),
-        limit.min(1000)
-    );
-    let payload: Value = client.get_json_cached("europepmc", &url).await?;
+    let base = &client.endpoints().europepmc;
+    let mut cursor = "*".to_string();
+
+    while batch.results.len() < limit {
+        let url = format!(
+            "{base}/search?query={}&format=json&pageSize={}&cursorMark={}",
+            urlencoding::encode(&q),
+            page_size,
+            urlencoding::encode(&cursor)
+        );
+        let payload: Value = match client.get_json_cached("europepmc", &url).await {
+            Ok(v) => v,
+            Err(err) => {
+                batch.record_page_error(err, &format!("page at cursorMark={cursor}"))?;
+                break;
+            }
+        };
+        batch.total_available = payload["hitCount"].as_u64().or(batch.total_available);
+
+        let page = parse_page(&payload);
+        let next = payload["nextCursorMark"].as_str().map(ToString::to_string);
+        if page.is_empty() {
+            break;
+        }
+        batch.results.extend(page);
+        match next {
+            Some(next) if next != cursor => cursor = next,
+            _ => break,
+        }
+    }
+
+    batch.results.truncate(limit);
+    Ok(batch)
+}
+
+/// Full-text links of open-access hits are stored under `CANONICAL_BASE`, so a mirror used
+/// for one run never ends up in the docs table.
+fn parse_page(payload: &Value) -> Vec<SearchResult> {
     let results = payload["resultList"]["result"]
         .as_array()
         .cloned()
@@ -27,9 +112,7 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str)
         let epmc_id = item["id"].as_str().map(ToString::to_string);
         let is_oa = item["isOpenAccess"].as_str().unwrap_or("N") == "Y";
         let oa_url = if is_oa {
-            epmc_id
-                .clone()
-                .map(|id| format!("https://www.ebi.ac.uk/europepmc/webservices/rest/{id}/fullTextXML"))
+            epmc_id.as_deref().map(|id| full_text_url(CANONICAL_BASE, id))
         } else {
             None
         };
@@ -37,6 +120,7 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str)
             source: "europepmc".to_string(),
             doi,
             pmid,
+            pmcid: None,
             title: item["title"].as_str().unwrap_or("Untitled").to_string(),
             journal: item["journalTitle"].as_str().map(ToString::to_string),
             year: item["pubYear"].as_str().and_then(|y| y.parse::<i32>().ok()),
@@ -45,6 +129,9 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str)
                 .map(|v| v.split(',').map(|x| x.trim().to_string()).collect())
                 .unwrap_or_default(),
             abstract_text: item["abstractText"].as_str().map(ToString::to_string),
+            mesh_headings: vec![],
+            publication_types: vec![],
+            keywords: vec![],
             oa_url,
             epmc_id,
             url: item["fullTextUrlList"]["fullTextUrl"]
@@ -52,16 +139,32 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str)
                 .and_then(|arr| arr.first())
                 .and_then(|x| x["url"].as_str())
                 .map(ToString::to_string),
+            trial: None,
+            trial_ids: vec![],
+            preprint: None,
+            sources: vec![],
+            field_sources: Default::default(),
+            fused_score: None,
         });
     }
 
-    Ok(out)
+    out
 }
 
 pub async fn get_jats_xml(client: &ApiClient, epmc_id: &str) -> Result<String> {
-    let url = format!(
-        "https://www.ebi.ac.uk/europepmc/webservices/rest/{}/fullTextXML",
-        epmc_id
-    );
+    let url = full_text_url(&client.endpoints().europepmc, epmc_id);
     client.get_text_cached("europepmc", &url).await
 }
+
+/// JATS full-text URL for a Europe PMC record id under `base`.
+pub fn full_text_url(base: &str, epmc_id: &str) -> String {
+    format!("{base}/{epmc_id}/fullTextXML")
+}
+
+/// Points a stored link under `CANONICAL_BASE` at the configured `base`; other URLs pass through.
+pub fn rebase_url(url: &str, base: &str) -> String {
+    match url.strip_prefix(CANONICAL_BASE) {
+        Some(rest) => format!("{base}{rest}"),
+        None => url.to_string(),
+    }
+}
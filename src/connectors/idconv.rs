@@ -0,0 +1,107 @@
+use crate::connectors::ApiClient;
+use crate::errors::Result;
+use serde_json::Value;
+
+/// The converter accepts at most 200 ids per request.
+pub const IDCONV_BATCH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdType {
+    Doi,
+    Pmid,
+    Pmcid,
+}
+
+impl IdType {
+    fn as_param(self) -> &'static str {
+        match self {
+            IdType::Doi => "doi",
+            IdType::Pmid => "pmid",
+            IdType::Pmcid => "pmcid",
+        }
+    }
+}
+
+/// Identifier set the converter returned for one requested id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdRecord {
+    pub requested: String,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub pmcid: Option<String>,
+}
+
+/// Converts ids of a single type in batches; ids the service cannot resolve are omitted.
+pub async fn convert(
+    client: &ApiClient,
+    ids: &[String],
+    id_type: IdType,
+    email: &str,
+) -> Result<Vec<IdRecord>> {
+    let mut out = Vec::new();
+    for chunk in ids.chunks(IDCONV_BATCH) {
+        let email_param = if email.trim().is_empty() {
+            String::new()
+        } else {
+            format!("&email={}", urlencoding::encode(email.trim()))
+        };
+        let url = format!(
+            "{}/?ids={}&idtype={}&format=json&tool=lit-harvester{}",
+            client.endpoints().idconv,
+            urlencoding::encode(&chunk.join(",")),
+            id_type.as_param(),
+            email_param
+        );
+        let payload: Value = client.get_json_cached("idconv", &url).await?;
+        out.extend(parse_records(&payload));
+    }
+    Ok(out)
+}
+
+pub fn parse_records(payload: &Value) -> Vec<IdRecord> {
+    payload["records"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r["status"].as_str() != Some("error"))
+        .filter_map(|r| {
+            Some(IdRecord {
+                requested: id_field(&r["requested-id"])?,
+                doi: id_field(&r["doi"]),
+                pmid: id_field(&r["pmid"]),
+                pmcid: id_field(&r["pmcid"]),
+            })
+        })
+        .collect()
+}
+
+/// Ids arrive as strings or, for PMIDs, sometimes as numbers.
+fn id_field(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_records;
+    use serde_json::json;
+
+    #[test]
+    fn parses_records_and_skips_errors() {
+        let payload = json!({
+            "status": "ok",
+            "records": [
+                {"requested-id": "10.1093/nar/gks1195", "pmcid": "PMC3531190", "pmid": 23193287, "doi": "10.1093/nar/gks1195"},
+                {"requested-id": "10.9999/missing", "status": "error", "errmsg": "invalid article id"}
+            ]
+        });
+        let records = parse_records(&payload);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].pmid.as_deref(), Some("23193287"));
+        assert_eq!(records[0].pmcid.as_deref(), Some("PMC3531190"));
+    }
+}
@@ -1,16 +1,26 @@
+pub mod biorxiv;
 pub mod clinicaltrials;
 pub mod crossref;
 pub mod epmc;
+pub mod idconv;
 pub mod openalex;
+pub mod pmc_oa;
 pub mod pubmed;
+pub mod registry;
 pub mod unpaywall;
 
 use crate::cache::http_cache::HttpCache;
-use crate::config::{AppConfig, RetryConfig};
-use crate::errors::{LitError, Result};
+use crate::config::{AppConfig, CassetteMode, EndpointsConfig, RetryConfig};
+use crate::errors::{HttpStatusError, LitError, Result};
+use crate::net::cassette::{Cassette, RecordedResponse};
+use crate::net::url::redact_url;
 use crate::net::ratelimit::RateLimitRegistry;
 use crate::net::retry::retry_with_backoff;
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use chrono::{NaiveDate, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT,
+};
 use serde::de::DeserializeOwned;
 
 #[derive(Clone)]
@@ -19,6 +29,9 @@ pub struct ApiClient {
     cache: HttpCache,
     ratelimits: RateLimitRegistry,
     retry_cfg: RetryConfig,
+    offline: bool,
+    cassette: Option<Cassette>,
+    endpoints: EndpointsConfig,
 }
 
 impl ApiClient {
@@ -30,57 +43,178 @@ impl ApiClient {
                 .unwrap_or_else(|_| HeaderValue::from_static("lit-harvester/0.1")),
         );
         let http = reqwest::Client::builder().default_headers(headers).build()?;
-        let cache = HttpCache::new(&cfg.data.cache_dir, 60 * 60 * 24)?;
+        let cache = HttpCache::new(&cfg.data.cache_dir, &cfg.cache)?;
+        let cassette = match cfg.cassette.mode {
+            CassetteMode::Off => None,
+            mode => Some(Cassette::new(&cfg.cassette.dir, mode)?),
+        };
         Ok(Self {
             http,
             cache,
             ratelimits: RateLimitRegistry::from_config(&cfg.rate_limits),
             retry_cfg: cfg.retry.clone(),
+            offline: cfg.network.offline,
+            cassette,
+            endpoints: cfg.endpoints.clone(),
         })
     }
 
+    pub fn cache(&self) -> &HttpCache {
+        &self.cache
+    }
+
+    /// Base URLs the connectors build their requests from.
+    pub fn endpoints(&self) -> &EndpointsConfig {
+        &self.endpoints
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// The day relative `--since` windows resolve against. Replaying a cassette uses its
+    /// recording day so requests rebuild the recorded URLs.
+    pub fn today(&self) -> NaiveDate {
+        self.cassette
+            .as_ref()
+            .and_then(Cassette::recorded_on)
+            .unwrap_or_else(|| Utc::now().date_naive())
+    }
+
+    /// Serves fresh cache hits directly; stale entries with an ETag or Last-Modified are
+    /// revalidated with a conditional request and reused on 304. While recording or
+    /// replaying a cassette the cache is not read, so every request reaches the cassette.
     pub async fn get_text_cached(&self, source: &str, url: &str) -> Result<String> {
+        self.get_text(source, url, true).await
+    }
+
+    /// Like `get_json_cached`, but never answered from the cache while online: for responses
+    /// that go stale server-side before any TTL (PubMed history handles). The response is
+    /// still cached so `--offline` can serve it.
+    pub async fn get_json_fresh<T: DeserializeOwned>(&self, source: &str, url: &str) -> Result<T> {
+        let text = self.get_text(source, url, false).await?;
+        decode_json(source, &text)
+    }
+
+    async fn get_text(&self, source: &str, url: &str, read_cache: bool) -> Result<String> {
         let key = HttpCache::key_for(source, url);
-        if let Some(hit) = self.cache.get(&key)? {
-            return Ok(hit);
+        let cached = if (self.cassette.is_some() || !read_cache) && !self.offline {
+            None
+        } else {
+            self.cache.get(source, &key)?
+        };
+        if self.offline {
+            return cached
+                .map(|c| c.body)
+                .ok_or_else(|| LitError::CacheMiss(source.to_string(), url.to_string()));
+        }
+        if let Some(hit) = cached.as_ref().filter(|c| c.fresh) {
+            return Ok(hit.body.clone());
         }
 
-        self.ratelimits.wait(source).await;
-        let body = retry_with_backoff(&self.retry_cfg, || async {
-            let resp = self.http.get(url).send().await?;
-            if !resp.status().is_success() {
-                return Err(LitError::External(format!(
-                    "{source} returned status {} for {url}",
-                    resp.status()
-                )));
+        let fetched = retry_with_backoff(&self.retry_cfg.for_source(source), || async {
+            let mut conditional = Vec::new();
+            if let Some(stale) = &cached {
+                conditional.extend(stale.etag.iter().map(|v| (IF_NONE_MATCH, v.clone())));
+                conditional.extend(
+                    stale
+                        .last_modified
+                        .iter()
+                        .map(|v| (IF_MODIFIED_SINCE, v.clone())),
+                );
+            }
+            let resp = self.send(source, url, &conditional).await?;
+            if resp.status == StatusCode::NOT_MODIFIED.as_u16() && cached.is_some() {
+                return Ok(None);
+            }
+            if !(200..300).contains(&resp.status) {
+                return Err(status_error(source, url, resp));
             }
-            Ok(resp.text().await?)
+            let etag = resp.headers.get(ETAG.as_str()).cloned();
+            let last_modified = resp.headers.get(LAST_MODIFIED.as_str()).cloned();
+            let body = String::from_utf8_lossy(&resp.body).into_owned();
+            Ok(Some((body, etag, last_modified)))
         })
         .await?;
 
-        self.cache.put(&key, &body)?;
-        Ok(body)
+        if let Some((body, etag, last_modified)) = fetched {
+            self.cache.put(source, url, &key, &body, etag, last_modified)?;
+            return Ok(body);
+        }
+        // 304 is only accepted when there was an entry to revalidate.
+        let stale = cached.ok_or_else(|| {
+            LitError::External(format!("{source} returned 304 for uncached {url}"))
+        })?;
+        tracing::debug!(source, url, "cache entry revalidated");
+        self.cache.touch(&key)?;
+        Ok(stale.body)
     }
 
     pub async fn get_json_cached<T: DeserializeOwned>(&self, source: &str, url: &str) -> Result<T> {
         let text = self.get_text_cached(source, url).await?;
-        serde_json::from_str(&text).map_err(|e| {
-            LitError::External(format!("failed to decode {source} response as json: {e}"))
-        })
+        decode_json(source, &text)
     }
 
     pub async fn download_bytes(&self, source: &str, url: &str) -> Result<Vec<u8>> {
-        self.ratelimits.wait(source).await;
-        retry_with_backoff(&self.retry_cfg, || async {
-            let resp = self.http.get(url).send().await?;
-            if !resp.status().is_success() {
-                return Err(LitError::External(format!(
-                    "download failed from {source} with status {} ({url})",
-                    resp.status()
-                )));
+        if self.offline {
+            return Err(LitError::Offline(format!("not downloading {url} from {source}")));
+        }
+        retry_with_backoff(&self.retry_cfg.for_source(source), || async {
+            let resp = self.send(source, url, &[]).await?;
+            if !(200..300).contains(&resp.status) {
+                return Err(status_error(source, url, resp));
             }
-            Ok(resp.bytes().await?.to_vec())
+            Ok(resp.body)
         })
         .await
     }
+
+    /// One GET on the wire, or from the cassette when replaying; records it when recording.
+    async fn send(
+        &self,
+        source: &str,
+        url: &str,
+        headers: &[(HeaderName, String)],
+    ) -> Result<RecordedResponse> {
+        if let Some(cassette) = &self.cassette
+            && cassette.mode() == CassetteMode::Replay
+        {
+            return cassette.replay(source, url);
+        }
+
+        self.ratelimits.wait(source).await;
+        let mut req = self.http.get(url);
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        let resp = req.send().await?;
+        let recorded = RecordedResponse {
+            status: resp.status().as_u16(),
+            headers: resp
+                .headers()
+                .iter()
+                .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+                .collect(),
+            body: resp.bytes().await?.to_vec(),
+        };
+        if let Some(cassette) = &self.cassette {
+            cassette.record(source, url, &recorded)?;
+        }
+        Ok(recorded)
+    }
+}
+
+fn decode_json<T: DeserializeOwned>(source: &str, text: &str) -> Result<T> {
+    serde_json::from_str(text).map_err(|e| {
+        LitError::External(format!("failed to decode {source} response as json: {e}"))
+    })
+}
+
+fn status_error(source: &str, url: &str, resp: RecordedResponse) -> LitError {
+    LitError::HttpStatus(Box::new(HttpStatusError {
+        service: source.to_string(),
+        url: redact_url(url),
+        status: resp.status,
+        headers: resp.headers,
+    }))
 }
@@ -1,48 +1,296 @@
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
 use crate::connectors::ApiClient;
 use crate::errors::Result;
+use crate::query::render::Dialect;
+use crate::query::window::DateWindow;
 use crate::types::SearchResult;
 use serde_json::Value;
 
-pub async fn search(client: &ApiClient, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-    let url = format!(
-        "https://api.openalex.org/works?search={}&per-page={}",
-        urlencoding::encode(query),
-        limit.min(200)
-    );
-    let payload: Value = client.get_json_cached("openalex", &url).await?;
-    let items = payload["results"].as_array().cloned().unwrap_or_default();
-    let mut out = Vec::new();
+pub struct OpenAlexConnector;
 
-    for item in items {
-        let doi = item["doi"].as_str().map(|s| s.replace("https://doi.org/", ""));
-        let year = item["publication_year"].as_i64().map(|v| v as i32);
-        let authors = item["authorships"]
-            .as_array()
-            .cloned()
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|a| a["author"]["display_name"].as_str().map(ToString::to_string))
-            .collect::<Vec<_>>();
-        let oa_url = item["open_access"]["oa_url"].as_str().map(ToString::to_string);
-        out.push(SearchResult {
-            source: "openalex".to_string(),
-            doi,
-            pmid: None,
-            title: item["display_name"]
-                .as_str()
-                .unwrap_or("Untitled")
-                .to_string(),
-            journal: item["primary_location"]["source"]["display_name"]
-                .as_str()
-                .map(ToString::to_string),
-            year,
-            authors,
-            abstract_text: None,
-            oa_url,
-            epmc_id: None,
-            url: item["id"].as_str().map(ToString::to_string),
-        });
+impl Connector for OpenAlexConnector {
+    fn name(&self) -> &'static str {
+        "openalex"
+    }
+
+    fn description(&self) -> &'static str {
+        "OpenAlex works API"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: true,
+        }
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::OpenAlex
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(search(client, &req.query, req.limit, &req.since))
     }
+}
+
+pub async fn search(
+    client: &ApiClient,
+    query: &str,
+    limit: usize,
+    since: &DateWindow,
+) -> Result<SourceBatch> {
+    let per_page = limit.clamp(1, 200);
+    let filter = window_filter(since);
+    let mut batch = SourceBatch {
+        since_applied: !filter.is_empty(),
+        ..SourceBatch::default()
+    };
+    let mut cursor = "*".to_string();
+
+    while batch.results.len() < limit {
+        let url = format!(
+            "{}/works?search={}&per-page={}&cursor={}{}",
+            client.endpoints().openalex,
+            urlencoding::encode(query),
+            per_page,
+            urlencoding::encode(&cursor),
+            filter
+        );
+        let payload: Value = match client.get_json_cached("openalex", &url).await {
+            Ok(v) => v,
+            Err(err) => {
+                batch.record_page_error(err, &format!("page at cursor={cursor}"))?;
+                break;
+            }
+        };
+        batch.total_available = payload["meta"]["count"].as_u64().or(batch.total_available);
+
+        let page = parse_page(&payload);
+        let next = payload["meta"]["next_cursor"].as_str().map(ToString::to_string);
+        if page.is_empty() {
+            break;
+        }
+        batch.results.extend(page);
+        match next {
+            Some(next) if next != cursor => cursor = next,
+            _ => break,
+        }
+    }
+
+    batch.results.truncate(limit);
+    Ok(batch)
+}
+
+fn parse_page(payload: &Value) -> Vec<SearchResult> {
+    payload["results"]
+        .as_array()
+        .map(|items| items.iter().map(parse_work).collect())
+        .unwrap_or_default()
+}
+
+fn parse_work(item: &Value) -> SearchResult {
+    let doi = item["doi"].as_str().map(|s| s.replace("https://doi.org/", ""));
+    let external_id = |key: &str| item["ids"][key].as_str().map(short_id);
+    let year = item["publication_year"].as_i64().map(|v| v as i32);
+    let authors = item["authorships"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|a| a["author"]["display_name"].as_str().map(ToString::to_string))
+        .collect::<Vec<_>>();
+    let oa_url = item["open_access"]["oa_url"].as_str().map(ToString::to_string);
+    SearchResult {
+        source: "openalex".to_string(),
+        doi,
+        pmid: external_id("pmid"),
+        pmcid: external_id("pmcid"),
+        title: item["display_name"]
+            .as_str()
+            .unwrap_or("Untitled")
+            .to_string(),
+        journal: item["primary_location"]["source"]["display_name"]
+            .as_str()
+            .map(ToString::to_string),
+        year,
+        authors,
+        abstract_text: None,
+        mesh_headings: vec![],
+        publication_types: vec![],
+        keywords: vec![],
+        oa_url,
+        epmc_id: None,
+        url: item["id"].as_str().map(ToString::to_string),
+        trial: None,
+        trial_ids: vec![],
+        preprint: None,
+        sources: vec![],
+        field_sources: Default::default(),
+        fused_score: None,
+    }
+}
+
+/// A work plus the citation edges needed for snowballing.
+#[derive(Debug, Clone)]
+pub struct CitationWork {
+    /// Short OpenAlex id, e.g. `W2741809807`.
+    pub id: String,
+    pub referenced_works: Vec<String>,
+    pub result: SearchResult,
+}
+
+impl CitationWork {
+    fn from_value(item: &Value) -> Option<Self> {
+        Some(Self {
+            id: short_id(item["id"].as_str()?),
+            referenced_works: item["referenced_works"]
+                .as_array()
+                .map(|ids| ids.iter().filter_map(Value::as_str).map(short_id).collect())
+                .unwrap_or_default(),
+            result: parse_work(item),
+        })
+    }
+}
 
+fn short_id(id: &str) -> String {
+    id.rsplit('/').next().unwrap_or(id).to_string()
+}
+
+/// Looks up a single work by DOI, falling back to PMID when the DOI lookup fails or finds
+/// nothing. `Ok(None)` when neither id is given or neither finds a work.
+pub async fn work_by_ids(
+    client: &ApiClient,
+    doi: Option<&str>,
+    pmid: Option<&str>,
+) -> Result<Option<CitationWork>> {
+    let keys = doi
+        .map(|doi| format!("doi:{doi}"))
+        .into_iter()
+        .chain(pmid.map(|pmid| format!("pmid:{pmid}")));
+    let mut last_err = None;
+    for key in keys {
+        let url = format!(
+            "{}/works/{}",
+            client.endpoints().openalex,
+            urlencoding::encode(&key)
+        );
+        match client.get_json_cached::<Value>("openalex", &url).await {
+            Ok(payload) => {
+                if let Some(work) = CitationWork::from_value(&payload) {
+                    return Ok(Some(work));
+                }
+            }
+            Err(err) => {
+                tracing::debug!(key = %key, error = %err, "openalex work lookup failed");
+                last_err = Some(err);
+            }
+        }
+    }
+    last_err.map_or(Ok(None), Err)
+}
+
+/// Fetches works by OpenAlex id, 50 per request (the `openalex` filter's OR limit).
+pub async fn works_by_openalex_ids(client: &ApiClient, ids: &[String]) -> Result<Vec<CitationWork>> {
+    let mut out = Vec::new();
+    for chunk in ids.chunks(50) {
+        let url = format!(
+            "{}/works?filter=openalex:{}&per-page=50",
+            client.endpoints().openalex,
+            chunk.join("|")
+        );
+        let payload: Value = client.get_json_cached("openalex", &url).await?;
+        out.extend(citation_page(&payload));
+    }
     Ok(out)
 }
+
+/// Works that cite `id`, up to `limit`.
+pub async fn citing_works(client: &ApiClient, id: &str, limit: usize) -> Result<Vec<CitationWork>> {
+    let per_page = limit.clamp(1, 200);
+    let mut out = Vec::new();
+    let mut cursor = "*".to_string();
+    while out.len() < limit {
+        let url = format!(
+            "{}/works?filter=cites:{id}&per-page={per_page}&cursor={}",
+            client.endpoints().openalex,
+            urlencoding::encode(&cursor)
+        );
+        let payload: Value = client.get_json_cached("openalex", &url).await?;
+        let page = citation_page(&payload);
+        if page.is_empty() {
+            break;
+        }
+        out.extend(page);
+        match payload["meta"]["next_cursor"].as_str() {
+            Some(next) if next != cursor => cursor = next.to_string(),
+            _ => break,
+        }
+    }
+    out.truncate(limit);
+    Ok(out)
+}
+
+fn citation_page(payload: &Value) -> Vec<CitationWork> {
+    payload["results"]
+        .as_array()
+        .map(|items| items.iter().filter_map(CitationWork::from_value).collect())
+        .unwrap_or_default()
+}
+
+fn window_filter(window: &DateWindow) -> String {
+    let mut parts = Vec::new();
+    if let Some(from) = window.from {
+        parts.push(format!("from_publication_date:{}", from.format("%Y-%m-%d")));
+    }
+    if let Some(to) = window.to {
+        parts.push(format!("to_publication_date:{}", to.format("%Y-%m-%d")));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("&filter={}", parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CitationWork, window_filter};
+    use crate::query::window::DateWindow;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    #[test]
+    fn renders_date_windows_as_publication_date_filters() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let window = |raw: &str| window_filter(&DateWindow::parse_at(raw, today).unwrap());
+        assert_eq!(window("30d"), "&filter=from_publication_date:2024-05-31");
+        assert_eq!(
+            window("2019..2023"),
+            "&filter=from_publication_date:2019-01-01,to_publication_date:2023-12-31"
+        );
+    }
+
+    #[test]
+    fn parses_citation_edges_and_external_ids() {
+        let item = json!({
+            "id": "https://openalex.org/W2741809807",
+            "doi": "https://doi.org/10.7717/peerj.4375",
+            "display_name": "The state of OA",
+            "publication_year": 2018,
+            "ids": {
+                "pmid": "https://pubmed.ncbi.nlm.nih.gov/29456894",
+                "pmcid": "https://www.ncbi.nlm.nih.gov/pmc/articles/PMC5815332"
+            },
+            "referenced_works": ["https://openalex.org/W1", "https://openalex.org/W2"]
+        });
+        let work = CitationWork::from_value(&item).expect("work");
+        assert_eq!(work.id, "W2741809807");
+        assert_eq!(work.referenced_works, vec!["W1", "W2"]);
+        assert_eq!(work.result.doi.as_deref(), Some("10.7717/peerj.4375"));
+        assert_eq!(work.result.pmid.as_deref(), Some("29456894"));
+        assert_eq!(work.result.pmcid.as_deref(), Some("PMC5815332"));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::connectors::ApiClient;
+use crate::errors::{LitError, Result};
+use flate2::read::GzDecoder;
+use roxmltree::Document;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Download links the PMC OA web service lists for one article.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OaPackage {
+    pub pmcid: String,
+    pub license: Option<String>,
+    pub tgz_url: Option<String>,
+    pub pdf_url: Option<String>,
+    pub retracted: bool,
+}
+
+/// What ended up on disk after unpacking a package.
+#[derive(Debug, Clone, Default)]
+pub struct UnpackedPackage {
+    /// The article's JATS file (`.nxml`, or `.xml` in older packages).
+    pub xml_path: Option<PathBuf>,
+    pub files: usize,
+}
+
+/// Looks up `pmcid` in the OA subset; `None` when the article is not open access.
+pub async fn lookup(client: &ApiClient, pmcid: &str) -> Result<Option<OaPackage>> {
+    let url = format!(
+        "{}?id={}",
+        client.endpoints().pmc_oa,
+        urlencoding::encode(pmcid)
+    );
+    let xml = client.get_text_cached("pmc_oa", &url).await?;
+    parse_oa_response(&xml)
+}
+
+pub fn parse_oa_response(xml: &str) -> Result<Option<OaPackage>> {
+    let doc = Document::parse(xml)?;
+    if let Some(err) = doc.descendants().find(|n| n.has_tag_name("error")) {
+        return match err.attribute("code") {
+            Some("idIsNotOpenAccess") | Some("idDoesNotExist") => Ok(None),
+            _ => Err(LitError::External(format!(
+                "pmc oa service error: {}",
+                err.text().unwrap_or_default().trim()
+            ))),
+        };
+    }
+    let Some(record) = doc.descendants().find(|n| n.has_tag_name("record")) else {
+        return Ok(None);
+    };
+
+    let mut package = OaPackage {
+        pmcid: record.attribute("id").unwrap_or_default().to_string(),
+        license: record
+            .attribute("license")
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(ToString::to_string),
+        retracted: record.attribute("retracted") == Some("yes"),
+        ..OaPackage::default()
+    };
+    for link in record.children().filter(|n| n.has_tag_name("link")) {
+        let Some(href) = link.attribute("href").map(https_url) else {
+            continue;
+        };
+        match link.attribute("format") {
+            Some("tgz") => package.tgz_url = Some(href),
+            Some("pdf") => package.pdf_url = Some(href),
+            _ => {}
+        }
+    }
+    Ok(Some(package))
+}
+
+/// The service still advertises `ftp://` links; the same paths are served over HTTPS.
+fn https_url(href: &str) -> String {
+    match href.strip_prefix("ftp://") {
+        Some(rest) => format!("https://{rest}"),
+        None => href.to_string(),
+    }
+}
+
+/// Unpacks a `.tar.gz` package into `dest`, dropping the top-level `PMCxxxx/` directory.
+/// Entries that would escape `dest` are skipped.
+pub fn unpack_package(bytes: &[u8], dest: &Path) -> Result<UnpackedPackage> {
+    fs::create_dir_all(dest)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    let mut out = UnpackedPackage::default();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        let Some(relative) = safe_relative(&path) else {
+            tracing::warn!(path = %path.display(), "skipping unsafe path in OA package");
+            continue;
+        };
+        let target = dest.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf)?;
+        fs::write(&target, &buf)?;
+        out.files += 1;
+
+        let ext = target
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match ext.as_deref() {
+            Some("nxml") => out.xml_path = Some(target),
+            Some("xml") if out.xml_path.is_none() => out.xml_path = Some(target),
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+fn safe_relative(path: &Path) -> Option<PathBuf> {
+    let mut parts = path
+        .components()
+        .map(|c| match c {
+            Component::Normal(p) => Some(p.to_owned()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if parts.len() > 1 {
+        parts.remove(0);
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_oa_response, unpack_package};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    #[test]
+    fn parses_package_links_and_rewrites_ftp() {
+        let xml = r#"<OA><responseDate>2024-01-01 00:00:00</responseDate>
+<request id="PMC5334499">https://www.ncbi.nlm.nih.gov/pmc/utils/oa/oa.fcgi?id=PMC5334499</request>
+<records returned-count="1" total-count="1">
+<record id="PMC5334499" citation="World J Radiol. 2017" license="CC BY" retracted="no">
+<link format="tgz" updated="2017-03-06 10:53:27" href="ftp://ftp.ncbi.nlm.nih.gov/pub/pmc/oa_package/8e/71/PMC5334499.tar.gz" />
+</record></records></OA>"#;
+        let package = parse_oa_response(xml).expect("parse").expect("package");
+        assert_eq!(package.pmcid, "PMC5334499");
+        assert_eq!(package.license.as_deref(), Some("CC BY"));
+        assert_eq!(
+            package.tgz_url.as_deref(),
+            Some("https://ftp.ncbi.nlm.nih.gov/pub/pmc/oa_package/8e/71/PMC5334499.tar.gz")
+        );
+
+        let closed = r#"<OA><error code="idIsNotOpenAccess">identifier 'PMC1' is not Open Access</error></OA>"#;
+        assert!(parse_oa_response(closed).expect("parse").is_none());
+    }
+
+    #[test]
+    fn unpacks_package_without_top_level_dir() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for (name, body) in [
+            ("PMC1/article.nxml", b"<article/>".as_slice()),
+            ("PMC1/gr1.jpg", b"jpeg".as_slice()),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, body).expect("append");
+        }
+        let bytes = builder.into_inner().expect("tar").finish().expect("gzip");
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let unpacked = unpack_package(&bytes, dir.path()).expect("unpack");
+        assert_eq!(unpacked.files, 2);
+        assert_eq!(unpacked.xml_path, Some(dir.path().join("article.nxml")));
+        assert!(dir.path().join("gr1.jpg").exists());
+    }
+}
@@ -1,38 +1,204 @@
+use crate::config::AppConfig;
+use crate::connectors::registry::{
+    Capabilities, Connector, SearchFuture, SearchRequest, SourceBatch,
+};
 use crate::connectors::ApiClient;
-use crate::errors::Result;
+use crate::domain::doc::{MeshHeading, UpdateStatus};
+use crate::errors::{LitError, Result};
+use crate::query::render::Dialect;
+use crate::query::window::DateWindow;
 use crate::types::SearchResult;
+use roxmltree::{Document, Node, ParsingOptions};
 use serde_json::Value;
+use std::collections::HashMap;
 
-pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str) -> Result<Vec<SearchResult>> {
-    let term = format!("{} AND {}", query, since_to_pubmed_clause(since));
-    let encoded_term = urlencoding::encode(&term);
+pub struct PubMedConnector {
+    api_key: String,
+}
+
+impl PubMedConnector {
+    pub fn new(cfg: &AppConfig) -> Self {
+        Self {
+            api_key: cfg.network.pubmed_api_key.clone(),
+        }
+    }
+}
+
+impl Connector for PubMedConnector {
+    fn name(&self) -> &'static str {
+        "pubmed"
+    }
+
+    fn description(&self) -> &'static str {
+        "NCBI PubMed via E-utilities (ESearch history server + ESummary/EFetch)"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            date_filter: true,
+            pagination: true,
+            oa_resolution: false,
+        }
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::PubMed
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a> {
+        Box::pin(async move {
+            search(client, &req.query, req.limit, &req.since, &self.api_key).await
+        })
+    }
+}
+
+/// ESummary/EFetch batch size; E-utilities accept up to 500 ids per history-server page.
+const SUMMARY_BATCH: usize = 200;
+pub async fn search(
+    client: &ApiClient,
+    query: &str,
+    limit: usize,
+    since: &DateWindow,
+    api_key: &str,
+) -> Result<SourceBatch> {
+    let eutils = &client.endpoints().pubmed;
+    let date_clause = window_clause(since);
+    let term = match &date_clause {
+        Some(clause) => format!("({query}) AND {clause}"),
+        None => query.to_string(),
+    };
     let esearch_url = format!(
-        "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi?db=pubmed&retmode=json&retmax={}&term={}",
-        limit.min(200),
-        encoded_term
+        "{eutils}/esearch.fcgi?db=pubmed&retmode=json&usehistory=y&retmax=0&term={}{}",
+        urlencoding::encode(&term),
+        api_key_param(api_key)
     );
 
-    let esearch: Value = client.get_json_cached("pubmed", &esearch_url).await?;
-    let ids = esearch["esearchresult"]["idlist"]
+    // The WebEnv expires server-side within hours, so the history ESearch is never replayed
+    // from the cache; the ESummary/EFetch pages are keyed on it and stay cacheable.
+    let esearch: Value = client.get_json_fresh("pubmed", &esearch_url).await?;
+    let history = HistoryHandle::from_esearch(&esearch)?;
+    let target = limit.min(history.count as usize);
+
+    let mut batch = SourceBatch {
+        total_available: Some(history.count),
+        since_applied: date_clause.is_some(),
+        ..SourceBatch::default()
+    };
+    let mut retstart = 0usize;
+    while retstart < target {
+        let retmax = SUMMARY_BATCH.min(target - retstart);
+        let esummary_url = format!(
+            "{eutils}/esummary.fcgi?db=pubmed&retmode=json&query_key={}&WebEnv={}&retstart={}&retmax={}{}",
+            urlencoding::encode(&history.query_key),
+            urlencoding::encode(&history.web_env),
+            retstart,
+            retmax,
+            api_key_param(api_key)
+        );
+        let summary: Value = match client.get_json_cached("pubmed", &esummary_url).await {
+            Ok(v) => v,
+            Err(err) => {
+                batch.record_page_error(err, &format!("esummary page at retstart={retstart}"))?;
+                break;
+            }
+        };
+
+        if let Some(err) = esummary_error(&summary) {
+            batch.record_page_error(
+                LitError::External(format!("pubmed esummary error: {err}")),
+                &format!("esummary page at retstart={retstart}"),
+            )?;
+            break;
+        }
+        let mut page = parse_esummary(&summary);
+        if page.is_empty() {
+            break;
+        }
+
+        let efetch_url = format!(
+            "{eutils}/efetch.fcgi?db=pubmed&retmode=xml&rettype=abstract&query_key={}&WebEnv={}&retstart={}&retmax={}{}",
+            urlencoding::encode(&history.query_key),
+            urlencoding::encode(&history.web_env),
+            retstart,
+            retmax,
+            api_key_param(api_key)
+        );
+        match client.get_text_cached("pubmed", &efetch_url).await {
+            Ok(xml) => match parse_efetch(&xml) {
+                Ok(records) => apply_efetch(&mut page, records),
+                Err(err) => batch
+                    .warnings
+                    .push(format!("efetch parse at retstart={retstart}: {err}")),
+            },
+            Err(err) => batch
+                .warnings
+                .push(format!("efetch page at retstart={retstart}: {err}")),
+        }
+
+        batch.results.extend(page);
+        retstart += retmax;
+    }
+
+    Ok(batch)
+}
+
+/// ESearch history-server handle (`usehistory=y`) plus the true hit count.
+#[derive(Debug, Clone)]
+struct HistoryHandle {
+    count: u64,
+    web_env: String,
+    query_key: String,
+}
+
+impl HistoryHandle {
+    fn from_esearch(esearch: &Value) -> Result<Self> {
+        let result = &esearch["esearchresult"];
+        if let Some(err) = result["ERROR"].as_str() {
+            return Err(LitError::External(format!("pubmed esearch error: {err}")));
+        }
+        let count = result["count"]
+            .as_str()
+            .and_then(|c| c.parse::<u64>().ok())
+            .unwrap_or(0);
+        let web_env = result["webenv"].as_str().unwrap_or_default().to_string();
+        let query_key = result["querykey"].as_str().unwrap_or_default().to_string();
+        if count > 0 && (web_env.is_empty() || query_key.is_empty()) {
+            return Err(LitError::External(
+                "pubmed esearch returned no history handle".to_string(),
+            ));
+        }
+        Ok(Self {
+            count,
+            web_env,
+            query_key,
+        })
+    }
+}
+
+/// Request-level ESummary failure, e.g. "Unable to obtain query #1" for an expired WebEnv.
+fn esummary_error(summary: &Value) -> Option<String> {
+    if let Some(err) = summary["error"].as_str() {
+        return Some(err.to_string());
+    }
+    let messages = summary["esummaryresult"]
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>();
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+fn parse_esummary(summary: &Value) -> Vec<SearchResult> {
+    let uids = summary["result"]["uids"]
         .as_array()
         .cloned()
         .unwrap_or_default()
         .into_iter()
         .filter_map(|v| v.as_str().map(ToString::to_string))
         .collect::<Vec<_>>();
-
-    if ids.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let esummary_url = format!(
-        "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esummary.fcgi?db=pubmed&retmode=json&id={}",
-        ids.join(",")
-    );
-    let summary: Value = client.get_json_cached("pubmed", &esummary_url).await?;
     let mut out = Vec::new();
 
-    for id in ids {
+    for id in uids {
         let item = &summary["result"][&id];
         if item.is_null() {
             continue;
@@ -56,26 +222,388 @@ pub async fn search(client: &ApiClient, query: &str, limit: usize, since: &str)
             source: "pubmed".to_string(),
             doi: item["elocationid"]
                 .as_str()
-                .and_then(|s| if s.starts_with("doi:") { Some(s[4..].trim().to_string()) } else { None }),
+                .and_then(|s| s.strip_prefix("doi:").map(|d| d.trim().to_string())),
             pmid: Some(id.clone()),
+            pmcid: None,
             title,
             journal,
             year,
             authors,
             abstract_text: None,
+            mesh_headings: vec![],
+            publication_types: vec![],
+            keywords: vec![],
             oa_url: None,
             epmc_id: None,
             url: Some(format!("https://pubmed.ncbi.nlm.nih.gov/{id}/")),
+            trial: None,
+            trial_ids: vec![],
+            preprint: None,
+            sources: vec![],
+            field_sources: Default::default(),
+            fused_score: None,
         });
     }
 
+    out
+}
+
+/// Indexing detail that only EFetch exposes (ESummary has no abstracts or MeSH).
+#[derive(Debug, Clone, Default)]
+pub struct PubmedRecord {
+    pub pmid: String,
+    pub doi: Option<String>,
+    pub pmcid: Option<String>,
+    pub abstract_text: Option<String>,
+    pub mesh_headings: Vec<MeshHeading>,
+    pub publication_types: Vec<String>,
+    pub keywords: Vec<String>,
+    /// ClinicalTrials.gov accessions from the secondary-source `DataBankList`.
+    pub registry_ids: Vec<String>,
+    /// Retraction/erratum/concern notices from `CommentsCorrectionsList`, as `(status, notice)`.
+    pub updates: Vec<(UpdateStatus, String)>,
+}
+
+pub fn parse_efetch(xml: &str) -> Result<Vec<PubmedRecord>> {
+    let opts = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let doc = Document::parse_with_options(xml, opts)?;
+    let mut out = Vec::new();
+
+    for article in doc.descendants().filter(|n| n.has_tag_name("PubmedArticle")) {
+        let Some(citation) = child(article, "MedlineCitation") else {
+            continue;
+        };
+        let Some(pmid) = child(citation, "PMID").map(node_text) else {
+            continue;
+        };
+        let mut record = PubmedRecord {
+            pmid,
+            ..PubmedRecord::default()
+        };
+
+        if let Some(abs) = child(citation, "Article").and_then(|a| child(a, "Abstract")) {
+            let parts = abs
+                .children()
+                .filter(|n| n.has_tag_name("AbstractText"))
+                .map(|n| match n.attribute("Label") {
+                    Some(label) => format!("{}: {}", label, node_text(n)),
+                    None => node_text(n),
+                })
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>();
+            if !parts.is_empty() {
+                record.abstract_text = Some(parts.join("\n"));
+            }
+        }
+
+        record.publication_types = citation
+            .descendants()
+            .filter(|n| n.has_tag_name("PublicationType"))
+            .map(node_text)
+            .collect();
+        record.keywords = citation
+            .descendants()
+            .filter(|n| n.has_tag_name("Keyword"))
+            .map(node_text)
+            .filter(|k| !k.is_empty())
+            .collect();
+        record.mesh_headings = citation
+            .descendants()
+            .filter(|n| n.has_tag_name("MeshHeading"))
+            .filter_map(|heading| {
+                let descriptor = child(heading, "DescriptorName")?;
+                let qualifiers = heading
+                    .children()
+                    .filter(|n| n.has_tag_name("QualifierName"))
+                    .collect::<Vec<_>>();
+                Some(MeshHeading {
+                    descriptor: node_text(descriptor),
+                    major_topic: descriptor.attribute("MajorTopicYN") == Some("Y")
+                        || qualifiers
+                            .iter()
+                            .any(|q| q.attribute("MajorTopicYN") == Some("Y")),
+                    qualifiers: qualifiers.into_iter().map(node_text).collect(),
+                })
+            })
+            .collect();
+
+        record.registry_ids = citation
+            .descendants()
+            .filter(|n| n.has_tag_name("DataBank"))
+            .filter(|bank| {
+                child(*bank, "DataBankName")
+                    .map(node_text)
+                    .is_some_and(|name| name.eq_ignore_ascii_case("ClinicalTrials.gov"))
+            })
+            .flat_map(|bank| bank.descendants().filter(|n| n.has_tag_name("AccessionNumber")))
+            .map(|n| node_text(n).to_uppercase())
+            .filter(|id| id.starts_with("NCT"))
+            .collect();
+
+        record.updates = citation
+            .descendants()
+            .filter(|n| n.has_tag_name("CommentsCorrections"))
+            .filter_map(|cc| {
+                let ref_type = cc.attribute("RefType")?;
+                let status = UpdateStatus::from_comments_corrections(ref_type)?;
+                let target = child(cc, "PMID")
+                    .map(|p| format!("PMID {}", node_text(p)))
+                    .or_else(|| child(cc, "RefSource").map(node_text))
+                    .unwrap_or_default();
+                Some((status, format!("pubmed {ref_type}: {target}")))
+            })
+            .collect();
+
+        let ids = child(article, "PubmedData")
+            .and_then(|d| child(d, "ArticleIdList"))
+            .into_iter()
+            .flat_map(|list| list.children().filter(|n| n.has_tag_name("ArticleId")));
+        for id in ids {
+            match id.attribute("IdType") {
+                Some("pmc") => record.pmcid = Some(node_text(id)),
+                Some("doi") => record.doi = Some(node_text(id)),
+                _ => {}
+            }
+        }
+
+        out.push(record);
+    }
+
+    Ok(out)
+}
+
+/// Retraction, erratum and expression-of-concern notices PubMed links to `pmid` through
+/// CommentsCorrections, as `(status, notice)`.
+pub async fn updates_for(
+    client: &ApiClient,
+    pmid: &str,
+    api_key: &str,
+) -> Result<Vec<(UpdateStatus, String)>> {
+    let url = format!(
+        "{}/efetch.fcgi?db=pubmed&retmode=xml&id={}{}",
+        client.endpoints().pubmed,
+        urlencoding::encode(pmid),
+        api_key_param(api_key)
+    );
+    let xml = client.get_text_cached("pubmed", &url).await?;
+    let mut out = parse_efetch(&xml)?
+        .into_iter()
+        .filter(|r| r.pmid == pmid)
+        .flat_map(|r| r.updates)
+        .collect::<Vec<_>>();
+    out.sort();
+    out.dedup();
     Ok(out)
 }
 
-fn since_to_pubmed_clause(since: &str) -> String {
-    if let Some(days) = since.strip_suffix('d').and_then(|d| d.parse::<i64>().ok()) {
-        format!("last {} days[dp]", days.max(1))
+fn apply_efetch(page: &mut [SearchResult], records: Vec<PubmedRecord>) {
+    let mut by_pmid = records
+        .into_iter()
+        .map(|r| (r.pmid.clone(), r))
+        .collect::<HashMap<_, _>>();
+    for item in page.iter_mut() {
+        let Some(record) = item.pmid.as_ref().and_then(|p| by_pmid.remove(p)) else {
+            continue;
+        };
+        item.abstract_text = record.abstract_text.or(item.abstract_text.take());
+        item.doi = item.doi.take().or(record.doi);
+        item.pmcid = record.pmcid;
+        item.mesh_headings = record.mesh_headings;
+        item.publication_types = record.publication_types;
+        item.keywords = record.keywords;
+        item.trial_ids = record.registry_ids;
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+fn node_text(node: Node<'_, '_>) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn api_key_param(api_key: &str) -> String {
+    if api_key.trim().is_empty() {
+        String::new()
     } else {
-        "last 30 days[dp]".to_string()
+        format!("&api_key={}", urlencoding::encode(api_key.trim()))
+    }
+}
+
+/// `[dp]` range clause; PubMed treats "3000" as an open upper bound.
+fn window_clause(window: &DateWindow) -> Option<String> {
+    if window.is_unbounded() {
+        return None;
+    }
+    let from = window
+        .from
+        .map(|d| d.format("%Y/%m/%d").to_string())
+        .unwrap_or_else(|| "1800".to_string());
+    let to = window
+        .to
+        .map(|d| d.format("%Y/%m/%d").to_string())
+        .unwrap_or_else(|| "3000".to_string());
+    Some(format!("(\"{from}\"[dp] : \"{to}\"[dp])"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistoryHandle, esummary_error, parse_efetch, parse_esummary};
+    use crate::domain::doc::UpdateStatus;
+    use serde_json::json;
+
+    #[test]
+    fn history_handle_reports_true_count() {
+        let esearch = json!({"esearchresult": {
+            "count": "1234", "retmax": "0", "webenv": "MCID_abc", "querykey": "1", "idlist": []
+        }});
+        let handle = HistoryHandle::from_esearch(&esearch).expect("handle");
+        assert_eq!(handle.count, 1234);
+        assert_eq!(handle.web_env, "MCID_abc");
+        assert_eq!(handle.query_key, "1");
+    }
+
+    #[test]
+    fn esummary_error_payloads_are_detected() {
+        assert_eq!(
+            esummary_error(&json!({"error": "Unable to obtain query #1"})).as_deref(),
+            Some("Unable to obtain query #1")
+        );
+        assert_eq!(
+            esummary_error(&json!({"esummaryresult": ["Unable to obtain query #1"]})).as_deref(),
+            Some("Unable to obtain query #1")
+        );
+        assert_eq!(esummary_error(&json!({"result": {"uids": []}})), None);
+    }
+
+    #[test]
+    fn esummary_page_keeps_uid_order() {
+        let summary = json!({"result": {
+            "uids": ["2", "1"],
+            "1": {"title": "First", "pubdate": "2023 Jan", "elocationid": "doi: 10.1/a"},
+            "2": {"title": "Second", "pubdate": "2024"}
+        }});
+        let rows = parse_esummary(&summary);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].pmid.as_deref(), Some("2"));
+        assert_eq!(rows[1].doi.as_deref(), Some("10.1/a"));
+        assert_eq!(rows[1].year, Some(2023));
+    }
+
+    #[test]
+    fn efetch_parses_structured_abstract_and_indexing() {
+        let xml = r#"<?xml version="1.0" ?>
+<!DOCTYPE PubmedArticleSet PUBLIC "-//NLM//DTD PubMedArticle, 1st January 2024//EN" "https://dtd.nlm.nih.gov/ncbi/pubmed/out/pubmed_240101.dtd">
+<PubmedArticleSet>
+  <PubmedArticle>
+    <MedlineCitation Status="MEDLINE" Owner="NLM">
+      <PMID Version="1">38000001</PMID>
+      <Article>
+        <Abstract>
+          <AbstractText Label="BACKGROUND">Clipping of <i>ruptured</i> aneurysms.</AbstractText>
+          <AbstractText Label="RESULTS">Occlusion in 92% of cases.</AbstractText>
+        </Abstract>
+        <PublicationTypeList>
+          <PublicationType UI="D016449">Randomized Controlled Trial</PublicationType>
+        </PublicationTypeList>
+        <DataBankList CompleteYN="Y">
+          <DataBank>
+            <DataBankName>ClinicalTrials.gov</DataBankName>
+            <AccessionNumberList><AccessionNumber>NCT01234567</AccessionNumber></AccessionNumberList>
+          </DataBank>
+          <DataBank>
+            <DataBankName>GENBANK</DataBankName>
+            <AccessionNumberList><AccessionNumber>AB123456</AccessionNumber></AccessionNumberList>
+          </DataBank>
+        </DataBankList>
+      </Article>
+      <MeshHeadingList>
+        <MeshHeading>
+          <DescriptorName UI="D002532" MajorTopicYN="N">Intracranial Aneurysm</DescriptorName>
+          <QualifierName UI="Q000601" MajorTopicYN="Y">surgery</QualifierName>
+        </MeshHeading>
+      </MeshHeadingList>
+      <KeywordList Owner="NOTNLM"><Keyword MajorTopicYN="N">microsurgery</Keyword></KeywordList>
+    </MedlineCitation>
+    <PubmedData>
+      <ArticleIdList>
+        <ArticleId IdType="pubmed">38000001</ArticleId>
+        <ArticleId IdType="doi">10.1000/jns.1</ArticleId>
+        <ArticleId IdType="pmc">PMC1234567</ArticleId>
+      </ArticleIdList>
+    </PubmedData>
+  </PubmedArticle>
+</PubmedArticleSet>"#;
+        let records = parse_efetch(xml).expect("parse");
+        assert_eq!(records.len(), 1);
+        let rec = &records[0];
+        assert_eq!(rec.pmid, "38000001");
+        assert_eq!(
+            rec.abstract_text.as_deref(),
+            Some("BACKGROUND: Clipping of ruptured aneurysms.\nRESULTS: Occlusion in 92% of cases.")
+        );
+        assert_eq!(rec.publication_types, vec!["Randomized Controlled Trial"]);
+        assert_eq!(rec.keywords, vec!["microsurgery"]);
+        assert_eq!(rec.mesh_headings[0].descriptor, "Intracranial Aneurysm");
+        assert_eq!(rec.mesh_headings[0].qualifiers, vec!["surgery"]);
+        assert!(rec.mesh_headings[0].major_topic);
+        assert_eq!(rec.pmcid.as_deref(), Some("PMC1234567"));
+        assert_eq!(rec.doi.as_deref(), Some("10.1000/jns.1"));
+        assert_eq!(rec.registry_ids, vec!["NCT01234567"]);
+    }
+
+    #[test]
+    fn errata_flag_the_corrected_article_not_the_notice() {
+        let xml = r#"<PubmedArticleSet>
+  <PubmedArticle>
+    <MedlineCitation>
+      <PMID>38000001</PMID>
+      <Article>
+        <PublicationTypeList><PublicationType>Journal Article</PublicationType></PublicationTypeList>
+      </Article>
+      <CommentsCorrectionsList>
+        <CommentsCorrections RefType="ErratumIn">
+          <RefSource>J Neurosurg. 2024;140(2):600</RefSource>
+          <PMID Version="1">38000002</PMID>
+        </CommentsCorrections>
+        <CommentsCorrections RefType="CommentIn">
+          <RefSource>J Neurosurg. 2024;140(2):580</RefSource>
+          <PMID Version="1">38000003</PMID>
+        </CommentsCorrections>
+      </CommentsCorrectionsList>
+    </MedlineCitation>
+  </PubmedArticle>
+  <PubmedArticle>
+    <MedlineCitation>
+      <PMID>38000002</PMID>
+      <Article>
+        <PublicationTypeList><PublicationType>Published Erratum</PublicationType></PublicationTypeList>
+      </Article>
+      <CommentsCorrectionsList>
+        <CommentsCorrections RefType="ErratumFor">
+          <RefSource>J Neurosurg. 2023;139(5):1200</RefSource>
+          <PMID Version="1">38000001</PMID>
+        </CommentsCorrections>
+      </CommentsCorrectionsList>
+    </MedlineCitation>
+  </PubmedArticle>
+</PubmedArticleSet>"#;
+        let records = parse_efetch(xml).expect("parse");
+        assert_eq!(
+            records[0].updates,
+            vec![(UpdateStatus::Corrected, "pubmed ErratumIn: PMID 38000002".to_string())]
+        );
+        assert!(records[1].updates.is_empty());
+        assert_eq!(UpdateStatus::from_publication_types(&records[1].publication_types), None);
     }
 }
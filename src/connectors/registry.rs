@@ -0,0 +1,144 @@
+use crate::config::AppConfig;
+use crate::connectors::biorxiv::PreprintConnector;
+use crate::connectors::clinicaltrials::ClinicalTrialsConnector;
+use crate::connectors::crossref::CrossrefConnector;
+use crate::connectors::epmc::EuropePmcConnector;
+use crate::connectors::openalex::OpenAlexConnector;
+use crate::connectors::pubmed::PubMedConnector;
+use crate::connectors::ApiClient;
+use crate::errors::{LitError, Result};
+use crate::query::ast::QueryNode;
+use crate::query::render::Dialect;
+use crate::query::window::DateWindow;
+use crate::types::SearchResult;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub type SearchFuture<'a> = Pin<Box<dyn Future<Output = Result<SourceBatch>> + Send + 'a>>;
+
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    /// `parsed` rendered in the connector's dialect.
+    pub query: String,
+    pub parsed: QueryNode,
+    pub limit: usize,
+    pub since: DateWindow,
+    /// Day `since` was resolved against; open-ended windows also end here.
+    pub today: NaiveDate,
+}
+
+/// What one connector returned for a request.
+#[derive(Debug, Clone, Default)]
+pub struct SourceBatch {
+    pub results: Vec<SearchResult>,
+    /// Hit count reported by the source, which may exceed `results.len()`.
+    pub total_available: Option<u64>,
+    /// Set when some results were retrieved before a later request failed.
+    pub partial_error: Option<String>,
+    /// Whether the source's native date filter restricted results to `since`.
+    pub since_applied: bool,
+    /// Problems that left every result in place but less complete, e.g. a failed EFetch
+    /// enrichment page; they do not make the source `partial`.
+    pub warnings: Vec<String>,
+}
+
+impl SourceBatch {
+    /// Keeps what was fetched when a follow-up page fails; a failure before any results is returned.
+    pub fn record_page_error(&mut self, err: LitError, context: &str) -> Result<()> {
+        if self.results.is_empty() {
+            return Err(err);
+        }
+        self.partial_error = Some(format!("{context}: {err}"));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Capabilities {
+    pub date_filter: bool,
+    pub pagination: bool,
+    pub oa_resolution: bool,
+}
+
+/// A discovery source that turns a query into `SearchResult`s.
+pub trait Connector: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn capabilities(&self) -> Capabilities;
+
+    /// Syntax `SearchRequest::query` is rendered in for this source.
+    fn dialect(&self) -> Dialect {
+        Dialect::FreeText
+    }
+
+    fn search<'a>(&'a self, client: &'a ApiClient, req: &'a SearchRequest) -> SearchFuture<'a>;
+}
+
+#[derive(Clone)]
+pub struct ConnectorRegistry {
+    connectors: Vec<Arc<dyn Connector>>,
+    enabled: Vec<String>,
+}
+
+impl ConnectorRegistry {
+    pub fn from_config(cfg: &AppConfig) -> Self {
+        let connectors: Vec<Arc<dyn Connector>> = vec![
+            Arc::new(PubMedConnector::new(cfg)),
+            Arc::new(EuropePmcConnector),
+            Arc::new(CrossrefConnector),
+            Arc::new(OpenAlexConnector),
+            Arc::new(ClinicalTrialsConnector),
+            Arc::new(PreprintConnector::biorxiv()),
+            Arc::new(PreprintConnector::medrxiv()),
+        ];
+        Self {
+            connectors,
+            enabled: cfg.sources.enabled.clone(),
+        }
+    }
+
+    pub fn all(&self) -> &[Arc<dyn Connector>] {
+        &self.connectors
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Connector>> {
+        self.connectors.iter().find(|c| c.name() == name).cloned()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.iter().any(|n| n == name)
+    }
+
+    /// Resolves `--sources` names, falling back to `[sources].enabled` when none are given.
+    pub fn resolve(&self, names: &[String]) -> Result<Vec<Arc<dyn Connector>>> {
+        let requested = if names.is_empty() { &self.enabled } else { names };
+        let mut out: Vec<Arc<dyn Connector>> = Vec::new();
+        for raw in requested {
+            let name = raw.trim().to_lowercase();
+            if name.is_empty() {
+                continue;
+            }
+            let connector = self.get(&name).ok_or_else(|| {
+                LitError::Validation(format!(
+                    "unknown source '{}' (available: {})",
+                    raw,
+                    self.names().join(", ")
+                ))
+            })?;
+            if !out.iter().any(|c| c.name() == connector.name()) {
+                out.push(connector);
+            }
+        }
+        if out.is_empty() {
+            return Err(LitError::Validation("no sources selected".to_string()));
+        }
+        Ok(out)
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.connectors.iter().map(|c| c.name()).collect()
+    }
+}
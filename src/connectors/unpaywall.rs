@@ -1,26 +1,134 @@
 use crate::connectors::ApiClient;
+use crate::domain::doc::{sort_locations, HostType, OaLocation, OaStatus, OaVersion};
 use crate::errors::{LitError, Result};
 use serde_json::Value;
 
-pub async fn resolve_oa_url(client: &ApiClient, doi: &str, email: &str) -> Result<Option<String>> {
+/// Everything Unpaywall knows about a DOI's free copies, best location first.
+#[derive(Debug, Clone)]
+pub struct OaLookup {
+    pub status: OaStatus,
+    pub locations: Vec<OaLocation>,
+}
+
+impl OaLookup {
+    pub fn preferred(&self) -> Option<&OaLocation> {
+        self.locations.iter().find(|l| l.best_url().is_some())
+    }
+}
+
+pub async fn lookup(client: &ApiClient, doi: &str, email: &str) -> Result<OaLookup> {
     if email.trim().is_empty() {
         return Err(LitError::Config(
             "UNPAYWALL_EMAIL is required for Unpaywall lookups".to_string(),
         ));
     }
     let url = format!(
-        "https://api.unpaywall.org/v2/{}?email={}",
+        "{}/{}?email={}",
+        client.endpoints().unpaywall,
         urlencoding::encode(doi),
         urlencoding::encode(email)
     );
     let payload: Value = client.get_json_cached("unpaywall", &url).await?;
+    Ok(parse_lookup(&payload))
+}
 
-    if let Some(best) = payload["best_oa_location"]["url_for_pdf"].as_str() {
-        return Ok(Some(best.to_string()));
+pub fn parse_lookup(payload: &Value) -> OaLookup {
+    let best_url = payload["best_oa_location"]["url"].as_str();
+    let mut locations = payload["oa_locations"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|loc| parse_location(loc, best_url))
+        .collect::<Vec<_>>();
+    if locations.is_empty() && payload["best_oa_location"].is_object() {
+        locations.push(parse_location(&payload["best_oa_location"], best_url));
     }
-    if let Some(best) = payload["best_oa_location"]["url"].as_str() {
-        return Ok(Some(best.to_string()));
+    sort_locations(&mut locations);
+
+    let status = match payload["oa_status"].as_str() {
+        Some(raw) => OaStatus::parse(raw),
+        None if locations.is_empty() => OaStatus::Unknown,
+        None => OaStatus::Open,
+    };
+    OaLookup { status, locations }
+}
+
+fn parse_location(loc: &Value, best_url: Option<&str>) -> OaLocation {
+    let url = str_field(&loc["url"]);
+    OaLocation {
+        is_best: best_url.is_some() && url.as_deref() == best_url,
+        url,
+        url_for_pdf: str_field(&loc["url_for_pdf"]),
+        license: str_field(&loc["license"]),
+        version: match loc["version"].as_str() {
+            Some("publishedVersion") => Some(OaVersion::Published),
+            Some("acceptedVersion") => Some(OaVersion::Accepted),
+            Some("submittedVersion") => Some(OaVersion::Submitted),
+            _ => None,
+        },
+        host_type: match loc["host_type"].as_str() {
+            Some("publisher") => Some(HostType::Publisher),
+            Some("repository") => Some(HostType::Repository),
+            _ => None,
+        },
+        evidence: str_field(&loc["evidence"]),
     }
+}
+
+fn str_field(v: &Value) -> Option<String> {
+    v.as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_lookup;
+    use crate::domain::doc::{HostType, OaStatus, OaVersion};
+    use serde_json::json;
 
-    Ok(None)
+    #[test]
+    fn keeps_all_locations_and_prefers_version_of_record() {
+        let payload = json!({
+            "oa_status": "hybrid",
+            "best_oa_location": {"url": "https://europepmc.org/articles/PMC1"},
+            "oa_locations": [
+                {
+                    "url": "https://europepmc.org/articles/PMC1",
+                    "url_for_pdf": "https://europepmc.org/articles/PMC1?pdf=render",
+                    "license": "cc-by-nc",
+                    "version": "acceptedVersion",
+                    "host_type": "repository",
+                    "evidence": "oa repository (via OAI-PMH doi match)"
+                },
+                {
+                    "url": "https://journal.example/article/1",
+                    "url_for_pdf": "https://journal.example/article/1.pdf",
+                    "license": "cc-by",
+                    "version": "publishedVersion",
+                    "host_type": "publisher",
+                    "evidence": "open (via page says license)"
+                }
+            ]
+        });
+
+        let lookup = parse_lookup(&payload);
+        assert_eq!(lookup.status, OaStatus::Hybrid);
+        assert_eq!(lookup.locations.len(), 2);
+        let preferred = lookup.preferred().expect("preferred location");
+        assert_eq!(preferred.version, Some(OaVersion::Published));
+        assert_eq!(preferred.host_type, Some(HostType::Publisher));
+        assert_eq!(preferred.license.as_deref(), Some("cc-by"));
+        assert_eq!(preferred.best_url(), Some("https://journal.example/article/1.pdf"));
+        assert!(lookup.locations[1].is_best);
+    }
+
+    #[test]
+    fn closed_dois_have_no_locations() {
+        let lookup = parse_lookup(&json!({"oa_status": "closed", "best_oa_location": null, "oa_locations": []}));
+        assert_eq!(lookup.status, OaStatus::Closed);
+        assert!(lookup.preferred().is_none());
+    }
 }
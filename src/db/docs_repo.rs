@@ -1,10 +1,13 @@
-use crate::db::schema::{normalize_doi, normalize_pmid, title_hash};
-use crate::domain::doc::{DocIdentity, DocRecord, OaStatus};
+use crate::db::schema::{normalize_doi, normalize_pmcid, normalize_pmid, title_hash};
+use crate::domain::doc::{DocIdentity, DocRecord, OaLocation, OaStatus, PreprintInfo, UpdateStatus};
 use crate::errors::{LitError, Result};
 use chrono::Utc;
 use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+const DOC_COLUMNS: &str = "doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at,pmcid,mesh_headings,publication_types,keywords,oa_locations,license,is_preprint,preprint_doi,update_status,update_notice";
+
 #[derive(Clone)]
 pub struct DocsRepo {
     db: crate::db::Db,
@@ -20,33 +23,79 @@ impl DocsRepo {
         let identity = DocIdentity {
             doi: item.doi.clone().map(|x| normalize_doi(&x)),
             pmid: item.pmid.clone().map(|x| normalize_pmid(&x)),
+            pmcid: item.pmcid.clone().map(|x| normalize_pmcid(&x)),
+            preprint_doi: item.preprint.as_ref().map(|p| normalize_doi(&p.doi)),
             title_hash: th.clone(),
             year: item.year,
             sha256: None,
         };
 
-        if let Some(existing) = self.find_existing(&identity)? {
+        if let Some(mut existing) = self.find_existing(&identity)? {
+            // A preprint seen before its journal version exists under its own DOI; once the
+            // server reports the published DOI, both records describe one paper.
+            if let Some(preprint_doi) = &identity.preprint_doi
+                && let Some(other) = self.find_by_preprint_doi(preprint_doi)?
+                && other.doc_id != existing.doc_id
+            {
+                let (kept, _) = self.merge_docs(&existing.doc_id, &other.doc_id)?;
+                existing = self
+                    .get_doc(&kept)?
+                    .ok_or_else(|| LitError::NotFound(kept.clone()))?;
+            }
             return self.merge(existing, item);
         }
 
         let now = Utc::now();
+        // A published preprint describes the journal doc: the preprint copy is only kept as a
+        // fallback location, and its type, license and green status stay with the preprint.
+        let is_preprint = item
+            .preprint
+            .as_ref()
+            .is_some_and(|p| p.published_doi.is_none());
+        let oa_url = own_oa_url(item);
         let doc = DocRecord {
             doc_id: format!("doc_{}", Uuid::new_v4()),
             doi: identity.doi.clone(),
             pmid: identity.pmid.clone(),
+            pmcid: identity.pmcid.clone(),
             title: item.title.clone(),
             journal: item.journal.clone(),
             year: item.year,
             authors: item.authors.clone(),
             abstract_text: item.abstract_text.clone(),
-            oa_status: OaStatus::from_oa_url(item.oa_url.as_deref()),
-            oa_url: item.oa_url.clone(),
+            mesh_headings: item.mesh_headings.clone(),
+            publication_types: if item.preprint.is_some() && !is_preprint {
+                journal_types(item.publication_types.clone())
+            } else {
+                item.publication_types.clone()
+            },
+            keywords: item.keywords.clone(),
+            oa_status: if is_preprint {
+                OaStatus::Green
+            } else {
+                OaStatus::from_oa_url(oa_url.as_deref())
+            },
+            access_needed: oa_url.is_none(),
+            oa_url,
+            oa_locations: item
+                .preprint
+                .as_ref()
+                .map(|p| p.locations())
+                .unwrap_or_default(),
+            license: item
+                .preprint
+                .as_ref()
+                .filter(|_| is_preprint)
+                .and_then(|p| p.license.clone()),
+            is_preprint,
+            preprint_doi: identity.preprint_doi.clone(),
+            update_status: None,
+            update_notice: None,
             epmc_id: item.epmc_id.clone(),
             local_pdf_path: None,
             local_xml_path: None,
             sha256: None,
             added_via: "oa".to_string(),
-            access_needed: item.oa_url.is_none(),
             title_hash: th,
             created_at: now,
             updated_at: now,
@@ -68,6 +117,8 @@ impl DocsRepo {
         let identity = DocIdentity {
             doi: doi.clone().map(|d| normalize_doi(&d)),
             pmid: pmid.clone().map(|p| normalize_pmid(&p)),
+            pmcid: None,
+            preprint_doi: None,
             title_hash: th.clone(),
             year: None,
             sha256: Some(sha256.clone()),
@@ -89,13 +140,23 @@ impl DocsRepo {
             doc_id: format!("doc_{}", Uuid::new_v4()),
             doi: doi.map(|d| normalize_doi(&d)),
             pmid: pmid.map(|p| normalize_pmid(&p)),
+            pmcid: None,
             title: title.to_string(),
             journal: None,
             year: None,
             authors: vec![],
             abstract_text: None,
+            mesh_headings: vec![],
+            publication_types: vec![],
+            keywords: vec![],
             oa_status: OaStatus::Unknown,
             oa_url: None,
+            oa_locations: vec![],
+            license: None,
+            is_preprint: false,
+            preprint_doi: None,
+            update_status: None,
+            update_notice: None,
             epmc_id: None,
             local_pdf_path,
             local_xml_path,
@@ -114,10 +175,10 @@ impl DocsRepo {
         let conn = self.db.conn();
         let conn = conn.lock().expect("db mutex poisoned");
         let mut stmt = conn.prepare(
-            "SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs WHERE doc_id = ?",
+            &format!("SELECT {DOC_COLUMNS} FROM docs WHERE doc_id = ?"),
         )?;
         let row = stmt
-            .query_row(params![doc_id], |r| map_doc_row(r))
+            .query_row(params![doc_id], map_doc_row)
             .optional()?;
         Ok(row)
     }
@@ -125,7 +186,9 @@ impl DocsRepo {
     pub fn list_docs(&self) -> Result<Vec<DocRecord>> {
         let conn = self.db.conn();
         let conn = conn.lock().expect("db mutex poisoned");
-        let mut stmt = conn.prepare("SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs ORDER BY created_at DESC")?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {DOC_COLUMNS} FROM docs ORDER BY created_at DESC"
+        ))?;
         let mut rows = stmt.query([])?;
         let mut out = Vec::new();
         while let Some(row) = rows.next()? {
@@ -138,7 +201,14 @@ impl DocsRepo {
         let docs = self
             .list_docs()?
             .into_iter()
-            .filter(|d| d.local_pdf_path.is_none() && d.local_xml_path.is_none() && (d.oa_url.is_some() || d.epmc_id.is_some()))
+            .filter(|d| {
+                d.local_pdf_path.is_none()
+                    && d.local_xml_path.is_none()
+                    && (d.oa_url.is_some()
+                        || d.epmc_id.is_some()
+                        || d.pmcid.is_some()
+                        || !d.oa_locations.is_empty())
+            })
             .collect::<Vec<_>>();
         Ok(if let Some(m) = max { docs.into_iter().take(m).collect() } else { docs })
     }
@@ -181,6 +251,47 @@ impl DocsRepo {
         Ok(())
     }
 
+    /// Stores Unpaywall detail. The preferred location's PDF link replaces `oa_url`; its
+    /// landing page is only used when no `oa_url` is stored yet.
+    pub fn update_oa(
+        &self,
+        doc_id: &str,
+        status: &OaStatus,
+        locations: &[OaLocation],
+        preferred: Option<&OaLocation>,
+    ) -> Result<()> {
+        let pdf_url = preferred.and_then(|l| l.url_for_pdf.as_deref());
+        let landing_url = preferred.and_then(|l| l.url.as_deref());
+        let license = preferred.and_then(|l| l.license.as_deref());
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        conn.execute(
+            "UPDATE docs SET oa_status = ?, oa_locations = ?, oa_url = COALESCE(?, oa_url, ?), license = COALESCE(?, license), access_needed = CASE WHEN COALESCE(?, oa_url, ?) IS NULL AND local_pdf_path IS NULL AND local_xml_path IS NULL THEN 1 ELSE 0 END, updated_at = ? WHERE doc_id = ?",
+            params![
+                status.as_str(),
+                serde_json::to_string(locations).unwrap_or_else(|_| "[]".to_string()),
+                pdf_url,
+                landing_url,
+                license,
+                pdf_url,
+                landing_url,
+                Utc::now().to_rfc3339(),
+                doc_id,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_license(&self, doc_id: &str, license: &str) -> Result<()> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        conn.execute(
+            "UPDATE docs SET license = ?, updated_at = ? WHERE doc_id = ?",
+            params![license, Utc::now().to_rfc3339(), doc_id],
+        )?;
+        Ok(())
+    }
+
     pub fn count_docs(&self) -> Result<usize> {
         let conn = self.db.conn();
         let conn = conn.lock().expect("db mutex poisoned");
@@ -199,47 +310,68 @@ impl DocsRepo {
         let conn = self.db.conn();
         let conn = conn.lock().expect("db mutex poisoned");
 
-        if let Some(doi) = &identity.doi {
-            if let Some(doc) = conn
+        if let Some(doi) = &identity.doi
+            && let Some(doc) = conn
                 .query_row(
-                    "SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs WHERE doi = ?",
+                    &format!("SELECT {DOC_COLUMNS} FROM docs WHERE doi = ?"),
                     params![doi],
                     map_doc_row,
                 )
                 .optional()?
-            {
-                return Ok(Some(doc));
-            }
+        {
+            return Ok(Some(doc));
         }
 
-        if let Some(pmid) = &identity.pmid {
-            if let Some(doc) = conn
+        if let Some(preprint_doi) = &identity.preprint_doi
+            && let Some(doc) = conn
                 .query_row(
-                    "SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs WHERE pmid = ?",
+                    &format!("SELECT {DOC_COLUMNS} FROM docs WHERE preprint_doi = ?1 OR doi = ?1"),
+                    params![preprint_doi],
+                    map_doc_row,
+                )
+                .optional()?
+        {
+            return Ok(Some(doc));
+        }
+
+        if let Some(pmid) = &identity.pmid
+            && let Some(doc) = conn
+                .query_row(
+                    &format!("SELECT {DOC_COLUMNS} FROM docs WHERE pmid = ?"),
                     params![pmid],
                     map_doc_row,
                 )
                 .optional()?
-            {
-                return Ok(Some(doc));
-            }
+        {
+            return Ok(Some(doc));
         }
 
-        if let Some(sha) = &identity.sha256 {
-            if let Some(doc) = conn
+        if let Some(pmcid) = &identity.pmcid
+            && let Some(doc) = conn
                 .query_row(
-                    "SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs WHERE sha256 = ?",
+                    &format!("SELECT {DOC_COLUMNS} FROM docs WHERE pmcid = ?"),
+                    params![pmcid],
+                    map_doc_row,
+                )
+                .optional()?
+        {
+            return Ok(Some(doc));
+        }
+
+        if let Some(sha) = &identity.sha256
+            && let Some(doc) = conn
+                .query_row(
+                    &format!("SELECT {DOC_COLUMNS} FROM docs WHERE sha256 = ?"),
                     params![sha],
                     map_doc_row,
                 )
                 .optional()?
-            {
-                return Ok(Some(doc));
-            }
+        {
+            return Ok(Some(doc));
         }
 
         let mut stmt = conn.prepare(
-            "SELECT doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at FROM docs WHERE title_hash = ?",
+            &format!("SELECT {DOC_COLUMNS} FROM docs WHERE title_hash = ?"),
         )?;
         let mut rows = stmt.query(params![identity.title_hash])?;
         while let Some(row) = rows.next()? {
@@ -252,7 +384,24 @@ impl DocsRepo {
         Ok(None)
     }
 
+    fn find_by_preprint_doi(&self, preprint_doi: &str) -> Result<Option<DocRecord>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        Ok(conn
+            .query_row(
+                &format!("SELECT {DOC_COLUMNS} FROM docs WHERE preprint_doi = ?1 OR doi = ?1"),
+                params![preprint_doi],
+                map_doc_row,
+            )
+            .optional()?)
+    }
+
     fn merge(&self, existing: DocRecord, item: &crate::types::SearchResult) -> Result<DocRecord> {
+        if let Some(preprint) = &item.preprint
+            && !existing.is_preprint
+        {
+            return self.attach_preprint(existing, preprint);
+        }
         let merged_oa = existing.oa_url.clone().or(item.oa_url.clone());
         let access_needed = merged_oa.is_none()
             && existing.local_pdf_path.is_none()
@@ -262,7 +411,7 @@ impl DocsRepo {
             let conn = self.db.conn();
             let conn = conn.lock().expect("db mutex poisoned");
             conn.execute(
-                "UPDATE docs SET title = COALESCE(NULLIF(?, ''), title), journal = COALESCE(?, journal), year = COALESCE(?, year), authors = COALESCE(?, authors), abstract = COALESCE(?, abstract), oa_url = COALESCE(?, oa_url), epmc_id = COALESCE(?, epmc_id), oa_status = ?, access_needed = ?, updated_at = ? WHERE doc_id = ?",
+                "UPDATE docs SET title = COALESCE(NULLIF(?, ''), title), journal = COALESCE(?, journal), year = COALESCE(?, year), authors = COALESCE(?, authors), abstract = COALESCE(?, abstract), oa_url = CASE WHEN COALESCE(oa_locations, '[]') <> '[]' THEN oa_url ELSE COALESCE(?, oa_url) END, epmc_id = COALESCE(?, epmc_id), mesh_headings = COALESCE(?, mesh_headings), publication_types = COALESCE(?, publication_types), keywords = COALESCE(?, keywords), oa_status = CASE WHEN oa_status IN ('gold', 'green', 'hybrid', 'bronze') THEN oa_status ELSE ? END, access_needed = ?, updated_at = ? WHERE doc_id = ?",
                 params![
                     item.title,
                     item.journal,
@@ -271,47 +420,412 @@ impl DocsRepo {
                     item.abstract_text,
                     item.oa_url,
                     item.epmc_id,
+                    non_empty_json(&item.mesh_headings),
+                    non_empty_json(&item.publication_types),
+                    non_empty_json(&item.keywords),
                     OaStatus::from_oa_url(merged_oa.as_deref()).as_str(),
                     access_needed as i32,
                     Utc::now().to_rfc3339(),
                     existing.doc_id,
                 ],
             )?;
+            let journal_version = item
+                .preprint
+                .as_ref()
+                .is_none_or(|p| p.published_doi.is_some());
+            if existing.is_preprint && journal_version {
+                // The journal version takes over `doi`; the preprint DOI moves to `preprint_doi`.
+                // The preprint's type, copy, license and status no longer describe the doc.
+                let types = if item.preprint.is_none() && !item.publication_types.is_empty() {
+                    item.publication_types.clone()
+                } else {
+                    existing.publication_types.clone()
+                };
+                let oa_url = own_oa_url(item).filter(|_| item.preprint.is_none());
+                let access_needed = oa_url.is_none()
+                    && existing.local_pdf_path.is_none()
+                    && existing.local_xml_path.is_none();
+                conn.execute(
+                    "UPDATE docs SET is_preprint = 0, preprint_doi = COALESCE(preprint_doi, doi), publication_types = ?, oa_url = ?, oa_status = ?, license = NULL, access_needed = ? WHERE doc_id = ?",
+                    params![
+                        serde_json::to_string(&journal_types(types)).unwrap_or_else(|_| "[]".to_string()),
+                        oa_url,
+                        OaStatus::from_oa_url(oa_url.as_deref()).as_str(),
+                        access_needed as i32,
+                        existing.doc_id
+                    ],
+                )?;
+                if let Some(doi) = item.doi.as_deref().map(normalize_doi) {
+                    conn.execute(
+                        "UPDATE docs SET doi = ?1 WHERE doc_id = ?2 AND NOT EXISTS (SELECT 1 FROM docs WHERE doi = ?1)",
+                        params![doi, existing.doc_id],
+                    )?;
+                }
+            } else if let Some(preprint) = &item.preprint {
+                conn.execute(
+                    "UPDATE docs SET oa_locations = ?, license = COALESCE(?, license) WHERE doc_id = ?",
+                    params![
+                        non_empty_json(&preprint.locations()),
+                        preprint.license,
+                        existing.doc_id
+                    ],
+                )?;
+            }
+            fill_missing_ids(
+                &conn,
+                &existing.doc_id,
+                item.doi.as_deref().map(normalize_doi),
+                item.pmid.as_deref().map(normalize_pmid),
+                item.pmcid.as_deref().map(normalize_pmcid),
+            )?;
         }
         self.get_doc(&existing.doc_id)?
             .ok_or_else(|| LitError::NotFound(existing.doc_id))
     }
 
-    fn insert_doc(&self, doc: &DocRecord) -> Result<()> {
+    /// Records a preprint of an already-stored journal version: its DOI, and its copy as a
+    /// fallback download when no other location is known. Journal metadata is left alone.
+    fn attach_preprint(&self, existing: DocRecord, preprint: &PreprintInfo) -> Result<DocRecord> {
+        {
+            let conn = self.db.conn();
+            let conn = conn.lock().expect("db mutex poisoned");
+            conn.execute(
+                "UPDATE docs SET preprint_doi = COALESCE(preprint_doi, ?), oa_locations = CASE WHEN COALESCE(oa_locations, '[]') = '[]' THEN ? ELSE oa_locations END, updated_at = ? WHERE doc_id = ?",
+                params![
+                    normalize_doi(&preprint.doi),
+                    serde_json::to_string(&preprint.locations()).unwrap_or_else(|_| "[]".to_string()),
+                    Utc::now().to_rfc3339(),
+                    existing.doc_id,
+                ],
+            )?;
+        }
+        self.get_doc(&existing.doc_id)?
+            .ok_or_else(|| LitError::NotFound(existing.doc_id))
+    }
+
+    /// Applies identifiers from an external converter. Ids the doc lacks are filled in; an id
+    /// that already belongs to another doc marks the two as duplicates, which are merged.
+    pub fn reconcile_ids(
+        &self,
+        doc_id: &str,
+        doi: Option<&str>,
+        pmid: Option<&str>,
+        pmcid: Option<&str>,
+    ) -> Result<ReconcileOutcome> {
+        let Some(before) = self.get_doc(doc_id)? else {
+            return Ok(ReconcileOutcome {
+                doc_id: doc_id.to_string(),
+                ..ReconcileOutcome::default()
+            });
+        };
+        let doi = doi.map(normalize_doi);
+        let pmid = pmid.map(normalize_pmid);
+        let pmcid = pmcid.map(normalize_pmcid);
+
+        let mut survivor = before.doc_id.clone();
+        let mut merged = Vec::new();
+        for (column, value) in [("doi", &doi), ("pmid", &pmid), ("pmcid", &pmcid)] {
+            let Some(value) = value.as_deref().filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            let owner: Option<String> = {
+                let conn = self.db.conn();
+                let conn = conn.lock().expect("db mutex poisoned");
+                conn.query_row(
+                    &format!("SELECT doc_id FROM docs WHERE {column} = ?"),
+                    params![value],
+                    |r| r.get(0),
+                )
+                .optional()?
+            };
+            if let Some(owner) = owner.filter(|o| o != &survivor) {
+                let (keep, dropped) = self.merge_docs(&survivor, &owner)?;
+                survivor = keep;
+                merged.push(dropped);
+            }
+        }
+
+        {
+            let conn = self.db.conn();
+            let conn = conn.lock().expect("db mutex poisoned");
+            fill_missing_ids(&conn, &survivor, doi, pmid, pmcid)?;
+        }
+        let after = self
+            .get_doc(&survivor)?
+            .ok_or_else(|| LitError::NotFound(survivor.clone()))?;
+        Ok(ReconcileOutcome {
+            filled: after.doi != before.doi || after.pmid != before.pmid || after.pmcid != before.pmcid,
+            doc_id: survivor,
+            merged,
+        })
+    }
+
+    /// Folds one duplicate into the other, keeping the older doc id. Returns `(kept, removed)`.
+    /// The removed id is kept as an alias, since its files, ledger and figure-index rows
+    /// still carry it.
+    pub fn merge_docs(&self, a: &str, b: &str) -> Result<(String, String)> {
+        let first = self
+            .get_doc(a)?
+            .ok_or_else(|| LitError::NotFound(a.to_string()))?;
+        let second = self
+            .get_doc(b)?
+            .ok_or_else(|| LitError::NotFound(b.to_string()))?;
+        let (keep, other) = if second.created_at < first.created_at {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        let kept_id = keep.doc_id.clone();
+        let removed_id = other.doc_id.clone();
+        let merged = combine_docs(keep, other);
+
+        let conn = self.db.conn();
+        let mut conn = conn.lock().expect("db mutex poisoned");
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM docs WHERE doc_id = ?", params![removed_id])?;
+        write_doc(&tx, &merged, "INSERT OR REPLACE")?;
+        tx.execute(
+            "UPDATE trials SET doc_id = ? WHERE doc_id = ?",
+            params![kept_id, removed_id],
+        )?;
+        tx.execute(
+            "UPDATE OR IGNORE doc_trial_links SET doc_id = ? WHERE doc_id = ?",
+            params![kept_id, removed_id],
+        )?;
+        tx.execute("DELETE FROM doc_trial_links WHERE doc_id = ?", params![removed_id])?;
+        tx.execute(
+            "UPDATE OR IGNORE doc_provenance SET doc_id = ? WHERE doc_id = ?",
+            params![kept_id, removed_id],
+        )?;
+        tx.execute("DELETE FROM doc_provenance WHERE doc_id = ?", params![removed_id])?;
+        tx.execute(
+            "UPDATE doc_aliases SET doc_id = ? WHERE doc_id = ?",
+            params![kept_id, removed_id],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO doc_aliases (alias_id, doc_id, created_at) VALUES (?, ?, ?)",
+            params![removed_id, kept_id, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok((kept_id, removed_id))
+    }
+
+    /// Doc ids removed by `merge_docs`, mapped to the doc they were merged into.
+    pub fn aliases(&self) -> Result<HashMap<String, String>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let mut stmt = conn.prepare("SELECT alias_id, doc_id FROM doc_aliases")?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<HashMap<_, _>>>()?)
+    }
+
+    /// The doc a merged-away id now lives under, or `doc_id` itself.
+    pub fn resolve_doc_id(&self, doc_id: &str) -> Result<String> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let merged: Option<String> = conn
+            .query_row(
+                "SELECT doc_id FROM doc_aliases WHERE alias_id = ?",
+                params![doc_id],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(merged.unwrap_or_else(|| doc_id.to_string()))
+    }
+
+    /// Records a retraction/correction; `None` clears a previous flag.
+    pub fn set_update_status(
+        &self,
+        doc_id: &str,
+        status: Option<UpdateStatus>,
+        notice: Option<&str>,
+    ) -> Result<()> {
         let conn = self.db.conn();
         let conn = conn.lock().expect("db mutex poisoned");
         conn.execute(
-            "INSERT INTO docs (doc_id,doi,pmid,title,journal,year,authors,abstract,oa_status,oa_url,epmc_id,local_pdf_path,local_xml_path,sha256,added_via,access_needed,title_hash,created_at,updated_at)
-             VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)",
+            "UPDATE docs SET update_status = ?, update_notice = ?, updated_at = ? WHERE doc_id = ?",
             params![
-                doc.doc_id,
-                doc.doi,
-                doc.pmid,
-                doc.title,
-                doc.journal,
-                doc.year,
-                serde_json::to_string(&doc.authors).unwrap_or_else(|_| "[]".to_string()),
-                doc.abstract_text,
-                doc.oa_status.as_str(),
-                doc.oa_url,
-                doc.epmc_id,
-                doc.local_pdf_path,
-                doc.local_xml_path,
-                doc.sha256,
-                doc.added_via,
-                doc.access_needed as i32,
-                doc.title_hash,
-                doc.created_at.to_rfc3339(),
-                doc.updated_at.to_rfc3339(),
+                status.as_ref().map(UpdateStatus::as_str),
+                notice,
+                Utc::now().to_rfc3339(),
+                doc_id
             ],
         )?;
         Ok(())
     }
+
+    /// Notes how a doc was reached beyond plain search, e.g. "snowball from doc_x".
+    pub fn add_provenance(&self, doc_id: &str, provenance: &str) -> Result<()> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        conn.execute(
+            "INSERT OR IGNORE INTO doc_provenance (doc_id, provenance, created_at) VALUES (?, ?, ?)",
+            params![doc_id, provenance, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn provenance(&self, doc_id: &str) -> Result<Vec<String>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let mut stmt = conn.prepare(
+            "SELECT provenance FROM doc_provenance WHERE doc_id = ? ORDER BY created_at, provenance",
+        )?;
+        let rows = stmt.query_map(params![doc_id], |r| r.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<String>>>()?)
+    }
+
+    fn insert_doc(&self, doc: &DocRecord) -> Result<()> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        write_doc(&conn, doc, "INSERT")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileOutcome {
+    /// Doc holding the identifiers afterwards; differs from the input when it was merged away.
+    pub doc_id: String,
+    /// Whether any DOI/PMID/PMCID was added.
+    pub filled: bool,
+    /// Duplicate doc ids removed by merging.
+    pub merged: Vec<String>,
+}
+
+/// Field-wise union of two records of the same paper, preferring `keep`'s values.
+fn combine_docs(keep: DocRecord, other: DocRecord) -> DocRecord {
+    fn or_list<T>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+        if a.is_empty() { b } else { a }
+    }
+    fn status_rank(status: &OaStatus) -> u8 {
+        match status {
+            s if s.is_detailed() => 3,
+            OaStatus::Open => 2,
+            OaStatus::Closed => 1,
+            _ => 0,
+        }
+    }
+
+    // When only one side is a preprint, access details come from the journal side; the
+    // preprint copy stays reachable through `oa_locations`.
+    let (oa_url, license, oa_status, publication_types) = if keep.is_preprint != other.is_preprint {
+        let (journal, preprint) = if keep.is_preprint {
+            (&other, &keep)
+        } else {
+            (&keep, &other)
+        };
+        (
+            journal.oa_url.clone(),
+            journal.license.clone(),
+            journal.oa_status.clone(),
+            journal_types(or_list(
+                journal.publication_types.clone(),
+                preprint.publication_types.clone(),
+            )),
+        )
+    } else {
+        let oa_status = if status_rank(&other.oa_status) > status_rank(&keep.oa_status) {
+            other.oa_status.clone()
+        } else {
+            keep.oa_status.clone()
+        };
+        (
+            keep.oa_url.clone().or(other.oa_url.clone()),
+            keep.license.clone().or(other.license.clone()),
+            oa_status,
+            or_list(keep.publication_types.clone(), other.publication_types.clone()),
+        )
+    };
+    let local_pdf_path = keep.local_pdf_path.or(other.local_pdf_path);
+    let local_xml_path = keep.local_xml_path.or(other.local_xml_path);
+    // When only `keep` is a preprint, the other side's DOI and title are the journal version's.
+    let journal_from_other = keep.is_preprint && !other.is_preprint;
+    let (doi, other_doi) = if journal_from_other {
+        (other.doi, keep.doi)
+    } else {
+        (keep.doi, other.doi)
+    };
+    let (title, title_hash) = if journal_from_other {
+        (other.title, other.title_hash)
+    } else {
+        (keep.title, keep.title_hash)
+    };
+    let preprint_doi = keep.preprint_doi.or(other.preprint_doi);
+    // Keep the more severe notice.
+    let (update_status, update_notice) = match (keep.update_status, other.update_status) {
+        (Some(a), Some(b)) if b < a => (Some(b), other.update_notice),
+        (None, Some(b)) => (Some(b), other.update_notice),
+        (a, _) => (a, keep.update_notice),
+    };
+    DocRecord {
+        doi: doi.or(other_doi),
+        title,
+        title_hash,
+        pmid: keep.pmid.or(other.pmid),
+        pmcid: keep.pmcid.or(other.pmcid),
+        journal: keep.journal.or(other.journal),
+        year: keep.year.or(other.year),
+        authors: or_list(keep.authors, other.authors),
+        abstract_text: keep.abstract_text.or(other.abstract_text),
+        mesh_headings: or_list(keep.mesh_headings, other.mesh_headings),
+        publication_types,
+        keywords: or_list(keep.keywords, other.keywords),
+        oa_status,
+        access_needed: oa_url.is_none() && local_pdf_path.is_none() && local_xml_path.is_none(),
+        oa_url,
+        oa_locations: or_list(keep.oa_locations, other.oa_locations),
+        license,
+        is_preprint: keep.is_preprint && other.is_preprint,
+        preprint_doi,
+        update_status,
+        update_notice,
+        epmc_id: keep.epmc_id.or(other.epmc_id),
+        local_pdf_path,
+        local_xml_path,
+        sha256: keep.sha256.or(other.sha256),
+        updated_at: Utc::now(),
+        ..keep
+    }
+}
+
+fn write_doc(conn: &rusqlite::Connection, doc: &DocRecord, verb: &str) -> Result<()> {
+    conn.execute(
+        &format!(
+            "{verb} INTO docs ({DOC_COLUMNS}) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)"
+        ),
+        params![
+            doc.doc_id,
+            doc.doi,
+            doc.pmid,
+            doc.title,
+            doc.journal,
+            doc.year,
+            serde_json::to_string(&doc.authors).unwrap_or_else(|_| "[]".to_string()),
+            doc.abstract_text,
+            doc.oa_status.as_str(),
+            doc.oa_url,
+            doc.epmc_id,
+            doc.local_pdf_path,
+            doc.local_xml_path,
+            doc.sha256,
+            doc.added_via,
+            doc.access_needed as i32,
+            doc.title_hash,
+            doc.created_at.to_rfc3339(),
+            doc.updated_at.to_rfc3339(),
+            doc.pmcid,
+            serde_json::to_string(&doc.mesh_headings).unwrap_or_else(|_| "[]".to_string()),
+            serde_json::to_string(&doc.publication_types).unwrap_or_else(|_| "[]".to_string()),
+            serde_json::to_string(&doc.keywords).unwrap_or_else(|_| "[]".to_string()),
+            serde_json::to_string(&doc.oa_locations).unwrap_or_else(|_| "[]".to_string()),
+            doc.license,
+            doc.is_preprint as i32,
+            doc.preprint_doi,
+            doc.update_status.as_ref().map(UpdateStatus::as_str),
+            doc.update_notice,
+        ],
+    )?;
+    Ok(())
 }
 
 fn map_doc_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DocRecord> {
@@ -324,13 +838,26 @@ fn map_doc_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DocRecord> {
         doc_id: row.get(0)?,
         doi: row.get(1)?,
         pmid: row.get(2)?,
+        pmcid: row.get(19)?,
         title: row.get(3)?,
         journal: row.get(4)?,
         year: row.get(5)?,
         authors,
         abstract_text: row.get(7)?,
+        mesh_headings: json_column(row, 20)?,
+        publication_types: json_column(row, 21)?,
+        keywords: json_column(row, 22)?,
         oa_status: OaStatus::parse(row.get::<_, String>(8)?.as_str()),
         oa_url: row.get(9)?,
+        oa_locations: json_column(row, 23)?,
+        license: row.get(24)?,
+        is_preprint: row.get::<_, i32>(25)? != 0,
+        preprint_doi: row.get(26)?,
+        update_status: row
+            .get::<_, Option<String>>(27)?
+            .as_deref()
+            .and_then(UpdateStatus::parse),
+        update_notice: row.get(28)?,
         epmc_id: row.get(10)?,
         local_pdf_path: row.get(11)?,
         local_xml_path: row.get(12)?,
@@ -346,3 +873,62 @@ fn map_doc_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DocRecord> {
             .with_timezone(&Utc),
     })
 }
+
+/// Sets identifiers the doc lacks, skipping any already owned by another doc.
+fn fill_missing_ids(
+    conn: &rusqlite::Connection,
+    doc_id: &str,
+    doi: Option<String>,
+    pmid: Option<String>,
+    pmcid: Option<String>,
+) -> Result<()> {
+    for (column, value) in [("doi", doi), ("pmid", pmid), ("pmcid", pmcid)] {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        conn.execute(
+            &format!(
+                "UPDATE docs SET {column} = ?1 WHERE doc_id = ?2 AND {column} IS NULL AND NOT EXISTS (SELECT 1 FROM docs WHERE {column} = ?1)"
+            ),
+            params![value, doc_id],
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn json_column<T: serde::de::DeserializeOwned + Default>(
+    row: &rusqlite::Row<'_>,
+    idx: usize,
+) -> rusqlite::Result<T> {
+    let raw: Option<String> = row.get(idx)?;
+    Ok(raw
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+/// JSON for list columns, `None` when empty so merges keep the stored value.
+fn non_empty_json<T: serde::Serialize>(items: &[T]) -> Option<String> {
+    if items.is_empty() {
+        None
+    } else {
+        serde_json::to_string(items).ok()
+    }
+}
+
+/// `item.oa_url`, unless the record is a published preprint pointing at its own copy.
+fn own_oa_url(item: &crate::types::SearchResult) -> Option<String> {
+    item.oa_url.clone().filter(|url| {
+        !item.preprint.as_ref().is_some_and(|p| {
+            p.published_doi.is_some()
+                && p.locations().iter().any(|l| l.url.as_ref() == Some(url))
+        })
+    })
+}
+
+/// Publication types for a journal-version doc: "Preprint" describes the other version.
+fn journal_types(types: Vec<String>) -> Vec<String> {
+    types
+        .into_iter()
+        .filter(|t| !t.eq_ignore_ascii_case("preprint"))
+        .collect()
+}
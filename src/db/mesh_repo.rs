@@ -0,0 +1,129 @@
+use crate::db::docs_repo::json_column;
+use crate::domain::mesh::{MeshDescriptor, normalize_term, term_keys};
+use crate::errors::Result;
+use chrono::Utc;
+use rusqlite::{OptionalExtension, params};
+
+#[derive(Clone)]
+pub struct MeshRepo {
+    db: crate::db::Db,
+}
+
+impl MeshRepo {
+    pub fn new(db: crate::db::Db) -> Self {
+        Self { db }
+    }
+
+    /// Replaces the loaded vocabulary wholesale; `source` names the dump it came from.
+    pub fn replace_all(&self, descriptors: &[MeshDescriptor], source: &str) -> Result<()> {
+        let conn = self.db.conn();
+        let mut conn = conn.lock().expect("db mutex poisoned");
+        let tx = conn.transaction()?;
+        tx.execute_batch(
+            "DELETE FROM mesh_descriptors; DELETE FROM mesh_tree; DELETE FROM mesh_terms; DELETE FROM mesh_meta;",
+        )?;
+        {
+            let mut insert_desc = tx.prepare(
+                "INSERT OR REPLACE INTO mesh_descriptors (ui, name, tree_numbers, entry_terms) VALUES (?, ?, ?, ?)",
+            )?;
+            let mut insert_tree =
+                tx.prepare("INSERT OR REPLACE INTO mesh_tree (tree_number, ui) VALUES (?, ?)")?;
+            let mut insert_term =
+                tx.prepare("INSERT OR IGNORE INTO mesh_terms (term_key, ui) VALUES (?, ?)")?;
+            for d in descriptors {
+                insert_desc.execute(params![
+                    d.ui,
+                    d.name,
+                    serde_json::to_string(&d.tree_numbers).unwrap_or_else(|_| "[]".to_string()),
+                    serde_json::to_string(&d.entry_terms).unwrap_or_else(|_| "[]".to_string()),
+                ])?;
+                for tree in &d.tree_numbers {
+                    insert_tree.execute(params![tree, d.ui])?;
+                }
+                for term in std::iter::once(&d.name).chain(&d.entry_terms) {
+                    for key in term_keys(term) {
+                        insert_term.execute(params![key, d.ui])?;
+                    }
+                }
+            }
+        }
+        tx.execute(
+            "INSERT INTO mesh_meta (key, value) VALUES ('source', ?), ('loaded_at', ?)",
+            params![source, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// "<file> (loaded <timestamp>)" for the loaded dump, or `None` when nothing is loaded.
+    pub fn source(&self) -> Result<Option<String>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let row = conn
+            .query_row(
+                "SELECT s.value, l.value FROM mesh_meta s JOIN mesh_meta l ON l.key = 'loaded_at' WHERE s.key = 'source'",
+                [],
+                |r| Ok(format!("{} (loaded {})", r.get::<_, String>(0)?, r.get::<_, String>(1)?)),
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM mesh_descriptors", [], |r| r.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Descriptor whose heading or entry term matches `term`; an exact heading match wins.
+    pub fn find_by_term(&self, term: &str) -> Result<Option<MeshDescriptor>> {
+        let key = normalize_term(term);
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let row = conn
+            .query_row(
+                "SELECT d.ui, d.name, d.tree_numbers, d.entry_terms FROM mesh_terms t \
+                 JOIN mesh_descriptors d ON d.ui = t.ui WHERE t.term_key = ? \
+                 ORDER BY lower(d.name) = ? DESC, d.ui LIMIT 1",
+                params![key, key],
+                map_descriptor_row,
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Descriptors one level below any of `descriptor`'s tree positions, by name.
+    pub fn narrower(&self, descriptor: &MeshDescriptor) -> Result<Vec<MeshDescriptor>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT d.ui, d.name, d.tree_numbers, d.entry_terms FROM mesh_tree t \
+             JOIN mesh_descriptors d ON d.ui = t.ui \
+             WHERE t.tree_number LIKE ? || '.%' AND instr(substr(t.tree_number, length(?) + 2), '.') = 0 \
+             ORDER BY d.name",
+        )?;
+        let mut out: Vec<MeshDescriptor> = Vec::new();
+        for tree in &descriptor.tree_numbers {
+            let mut rows = stmt.query(params![tree, tree])?;
+            while let Some(row) = rows.next()? {
+                let child = map_descriptor_row(row)?;
+                if child.ui != descriptor.ui && !out.iter().any(|d| d.ui == child.ui) {
+                    out.push(child);
+                }
+            }
+        }
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(out)
+    }
+}
+
+fn map_descriptor_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<MeshDescriptor> {
+    Ok(MeshDescriptor {
+        ui: row.get(0)?,
+        name: row.get(1)?,
+        tree_numbers: json_column(row, 2)?,
+        entry_terms: json_column(row, 3)?,
+    })
+}
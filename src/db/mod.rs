@@ -1,5 +1,7 @@
 pub mod docs_repo;
+pub mod mesh_repo;
 pub mod schema;
+pub mod trials_repo;
 
 use crate::errors::Result;
 use rusqlite::Connection;
@@ -31,9 +33,65 @@ impl Db {
     }
 
     pub fn migrate(&self) -> Result<()> {
-        let conn = self.conn.lock().expect("db mutex poisoned");
+        let mut conn = self.conn.lock().expect("db mutex poisoned");
         conn.execute_batch(include_str!("../../migrations/0001_init.sql"))?;
         conn.execute_batch(include_str!("../../migrations/0002_indexes.sql"))?;
-        Ok(())
+        apply_versioned(&mut conn, VERSIONED_MIGRATIONS)
+    }
+}
+
+/// Applies each migration newer than `user_version` together with its version bump in one
+/// transaction, so a migration that fails partway leaves neither its changes nor the bump.
+fn apply_versioned(conn: &mut Connection, migrations: &[(i64, &str)]) -> Result<()> {
+    let current: i64 = conn.pragma_query_value(None, "user_version", |r| r.get(0))?;
+    for (version, sql) in migrations {
+        if *version > current {
+            let tx = conn.transaction()?;
+            tx.execute_batch(sql)?;
+            tx.pragma_update(None, "user_version", version)?;
+            tx.commit()?;
+        }
+    }
+    Ok(())
+}
+
+/// Non-idempotent migrations (e.g. `ALTER TABLE`), applied once and tracked via `PRAGMA user_version`.
+const VERSIONED_MIGRATIONS: &[(i64, &str)] = &[
+    (3, include_str!("../../migrations/0003_pubmed_indexing.sql")),
+    (4, include_str!("../../migrations/0004_trials.sql")),
+    (5, include_str!("../../migrations/0005_doc_trial_links.sql")),
+    (6, include_str!("../../migrations/0006_oa_locations.sql")),
+    (7, include_str!("../../migrations/0007_preprints.sql")),
+    (8, include_str!("../../migrations/0008_doc_provenance.sql")),
+    (9, include_str!("../../migrations/0009_update_status.sql")),
+    (10, include_str!("../../migrations/0010_mesh.sql")),
+    (11, include_str!("../../migrations/0011_doc_aliases.sql")),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::apply_versioned;
+    use rusqlite::Connection;
+
+    #[test]
+    fn a_failed_migration_rolls_back_with_its_version() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch("CREATE TABLE docs (doc_id TEXT PRIMARY KEY);")
+            .expect("table");
+        let broken: &[(i64, &str)] = &[(
+            1,
+            "ALTER TABLE docs ADD COLUMN a TEXT; ALTER TABLE missing ADD COLUMN b TEXT;",
+        )];
+        assert!(apply_versioned(&mut conn, broken).is_err());
+
+        let fixed: &[(i64, &str)] = &[(
+            1,
+            "ALTER TABLE docs ADD COLUMN a TEXT; ALTER TABLE docs ADD COLUMN b TEXT;",
+        )];
+        apply_versioned(&mut conn, fixed).expect("rerun after rollback");
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |r| r.get(0))
+            .expect("version");
+        assert_eq!(version, 1);
     }
 }
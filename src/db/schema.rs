@@ -8,6 +8,15 @@ pub fn normalize_pmid(pmid: &str) -> String {
     pmid.trim().to_string()
 }
 
+pub fn normalize_pmcid(pmcid: &str) -> String {
+    let trimmed = pmcid.trim().to_uppercase();
+    if trimmed.starts_with("PMC") {
+        trimmed
+    } else {
+        format!("PMC{trimmed}")
+    }
+}
+
 pub fn title_hash(title: &str) -> String {
     let normalized = title
         .to_lowercase()
@@ -0,0 +1,164 @@
+use crate::db::docs_repo::json_column;
+use crate::domain::trial::{DocTrialLink, TrialRecord};
+use crate::errors::Result;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+
+const TRIAL_COLUMNS: &str = "nct_id,doc_id,title,official_title,phases,overall_status,start_date,completion_date,enrollment,enrollment_type,conditions,interventions,primary_outcomes,secondary_outcomes,sponsor,has_results,updated_at";
+
+#[derive(Clone)]
+pub struct TrialsRepo {
+    db: crate::db::Db,
+}
+
+impl TrialsRepo {
+    pub fn new(db: crate::db::Db) -> Self {
+        Self { db }
+    }
+
+    /// Inserts or refreshes a trial; registry data replaces the stored protocol wholesale.
+    pub fn upsert(&self, trial: &TrialRecord) -> Result<()> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        conn.execute(
+            &format!(
+                "INSERT INTO trials ({TRIAL_COLUMNS}) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?) \
+                 ON CONFLICT(nct_id) DO UPDATE SET doc_id = COALESCE(excluded.doc_id, trials.doc_id), title = excluded.title, official_title = excluded.official_title, phases = excluded.phases, overall_status = excluded.overall_status, start_date = excluded.start_date, completion_date = excluded.completion_date, enrollment = excluded.enrollment, enrollment_type = excluded.enrollment_type, conditions = excluded.conditions, interventions = excluded.interventions, primary_outcomes = excluded.primary_outcomes, secondary_outcomes = excluded.secondary_outcomes, sponsor = excluded.sponsor, has_results = excluded.has_results, updated_at = excluded.updated_at"
+            ),
+            params![
+                trial.nct_id,
+                trial.doc_id,
+                trial.title,
+                trial.official_title,
+                serde_json::to_string(&trial.phases).unwrap_or_else(|_| "[]".to_string()),
+                trial.overall_status,
+                trial.start_date,
+                trial.completion_date,
+                trial.enrollment,
+                trial.enrollment_type,
+                serde_json::to_string(&trial.conditions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&trial.interventions).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&trial.primary_outcomes).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&trial.secondary_outcomes).unwrap_or_else(|_| "[]".to_string()),
+                trial.sponsor,
+                trial.has_results as i32,
+                trial.updated_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(&self, nct_id: &str) -> Result<Option<TrialRecord>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let row = conn
+            .query_row(
+                &format!("SELECT {TRIAL_COLUMNS} FROM trials WHERE nct_id = ?"),
+                params![nct_id.to_uppercase()],
+                map_trial_row,
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    pub fn list(&self) -> Result<Vec<TrialRecord>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {TRIAL_COLUMNS} FROM trials ORDER BY start_date DESC, nct_id"
+        ))?;
+        let mut rows = stmt.query([])?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            out.push(map_trial_row(row)?);
+        }
+        Ok(out)
+    }
+
+    pub fn for_doc(&self, doc_id: &str) -> Result<Option<TrialRecord>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let row = conn
+            .query_row(
+                &format!("SELECT {TRIAL_COLUMNS} FROM trials WHERE doc_id = ?"),
+                params![doc_id],
+                map_trial_row,
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Records that `doc_id` reports or mentions `nct_id`; repeated links from the same source
+    /// are ignored.
+    pub fn link_doc(&self, doc_id: &str, nct_id: &str, source: &str) -> Result<()> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        conn.execute(
+            "INSERT OR IGNORE INTO doc_trial_links (doc_id, nct_id, source, created_at) VALUES (?, ?, ?, ?)",
+            params![doc_id, nct_id.to_uppercase(), source, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn links_for_doc(&self, doc_id: &str) -> Result<Vec<DocTrialLink>> {
+        self.query_links(
+            "SELECT doc_id, nct_id, source, created_at FROM doc_trial_links WHERE doc_id = ? ORDER BY nct_id, source",
+            doc_id,
+        )
+    }
+
+    /// Links for a trial, excluding the trial's own registry doc.
+    pub fn links_for_trial(&self, nct_id: &str) -> Result<Vec<DocTrialLink>> {
+        self.query_links(
+            "SELECT l.doc_id, l.nct_id, l.source, l.created_at FROM doc_trial_links l \
+             WHERE l.nct_id = ? AND NOT EXISTS (SELECT 1 FROM trials t WHERE t.nct_id = l.nct_id AND t.doc_id = l.doc_id) \
+             ORDER BY l.doc_id, l.source",
+            &nct_id.to_uppercase(),
+        )
+    }
+
+    fn query_links(&self, sql: &str, key: &str) -> Result<Vec<DocTrialLink>> {
+        let conn = self.db.conn();
+        let conn = conn.lock().expect("db mutex poisoned");
+        let mut stmt = conn.prepare(sql)?;
+        let mut rows = stmt.query(params![key])?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            let created_at_raw: String = row.get(3)?;
+            out.push(DocTrialLink {
+                doc_id: row.get(0)?,
+                nct_id: row.get(1)?,
+                source: row.get(2)?,
+                created_at: chrono::DateTime::parse_from_rfc3339(&created_at_raw)
+                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e)))?
+                    .with_timezone(&Utc),
+            });
+        }
+        Ok(out)
+    }
+}
+
+fn map_trial_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TrialRecord> {
+    let updated_at_raw: String = row.get(16)?;
+    Ok(TrialRecord {
+        nct_id: row.get(0)?,
+        doc_id: row.get(1)?,
+        title: row.get(2)?,
+        official_title: row.get(3)?,
+        phases: json_column(row, 4)?,
+        overall_status: row.get(5)?,
+        start_date: row.get(6)?,
+        completion_date: row.get(7)?,
+        enrollment: row.get(8)?,
+        enrollment_type: row.get(9)?,
+        conditions: json_column(row, 10)?,
+        interventions: json_column(row, 11)?,
+        primary_outcomes: json_column(row, 12)?,
+        secondary_outcomes: json_column(row, 13)?,
+        sponsor: row.get(14)?,
+        has_results: row.get::<_, i32>(15)? != 0,
+        updated_at: chrono::DateTime::parse_from_rfc3339(&updated_at_raw)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(16, rusqlite::types::Type::Text, Box::new(e)))?
+            .with_timezone(&Utc),
+    })
+}
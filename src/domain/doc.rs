@@ -1,26 +1,229 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum OaStatus {
+    /// Published open access in an OA journal.
+    Gold,
+    /// Free copy in a repository only.
+    Green,
+    /// Openly licensed in a subscription journal.
+    Hybrid,
+    /// Free to read on the publisher site without an open license.
+    Bronze,
+    /// Open, but no Unpaywall detail is known.
     Open,
     Closed,
+    #[default]
     Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum OaVersion {
+    Published,
+    Accepted,
+    Submitted,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum HostType {
+    Publisher,
+    Repository,
+}
+
+/// One place a free copy lives, as reported by Unpaywall.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OaLocation {
+    pub url: Option<String>,
+    pub url_for_pdf: Option<String>,
+    pub license: Option<String>,
+    pub version: Option<OaVersion>,
+    pub host_type: Option<HostType>,
+    pub evidence: Option<String>,
+    #[serde(default)]
+    pub is_best: bool,
+}
+
+impl OaLocation {
+    /// Version of record first, then accepted/submitted; publisher copies before repository
+    /// copies within a version, and direct PDF links before landing pages.
+    pub fn preference_key(&self) -> (u8, u8, u8) {
+        let version = match self.version {
+            Some(OaVersion::Published) => 0,
+            Some(OaVersion::Accepted) => 1,
+            Some(OaVersion::Submitted) => 2,
+            None => 3,
+        };
+        let host = match self.host_type {
+            Some(HostType::Publisher) => 0,
+            Some(HostType::Repository) => 1,
+            None => 2,
+        };
+        (version, host, u8::from(self.url_for_pdf.is_none()))
+    }
+
+    pub fn best_url(&self) -> Option<&str> {
+        self.url_for_pdf.as_deref().or(self.url.as_deref())
+    }
+}
+
+/// Orders locations by `OaLocation::preference_key`.
+pub fn sort_locations(locations: &mut [OaLocation]) {
+    locations.sort_by_key(OaLocation::preference_key);
+}
+
+/// Post-publication notice recorded against a doc. Variants are ordered most severe first.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStatus {
+    Retracted,
+    ExpressionOfConcern,
+    Corrected,
+}
+
+impl UpdateStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateStatus::Retracted => "retracted",
+            UpdateStatus::ExpressionOfConcern => "expression_of_concern",
+            UpdateStatus::Corrected => "corrected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "retracted" => Some(UpdateStatus::Retracted),
+            "expression_of_concern" => Some(UpdateStatus::ExpressionOfConcern),
+            "corrected" => Some(UpdateStatus::Corrected),
+            _ => None,
+        }
+    }
+
+    /// Maps a Crossref `update-to` type; unrelated update types (e.g. `addendum`) map to `None`.
+    pub fn from_crossref_type(kind: &str) -> Option<Self> {
+        match kind.to_lowercase().replace('-', "_").as_str() {
+            "retraction" | "withdrawal" | "removal" => Some(UpdateStatus::Retracted),
+            "expression_of_concern" => Some(UpdateStatus::ExpressionOfConcern),
+            "correction" | "erratum" | "corrigendum" | "partial_retraction" => {
+                Some(UpdateStatus::Corrected)
+            }
+            _ => None,
+        }
+    }
+
+    /// Maps a PubMed CommentsCorrections `RefType` pointing from an article to its notice.
+    pub fn from_comments_corrections(ref_type: &str) -> Option<Self> {
+        match ref_type {
+            "RetractionIn" => Some(UpdateStatus::Retracted),
+            "ExpressionOfConcernIn" => Some(UpdateStatus::ExpressionOfConcern),
+            "ErratumIn" | "RepublishedIn" => Some(UpdateStatus::Corrected),
+            _ => None,
+        }
+    }
+
+    /// Reads PubMed publication types such as "Retracted Publication". "Published Erratum"
+    /// is the type of the notice itself, so it flags nothing; the corrected article is found
+    /// through its `ErratumIn` relation instead.
+    pub fn from_publication_types(types: &[String]) -> Option<(Self, String)> {
+        types
+            .iter()
+            .filter_map(|t| {
+                let status = match t.to_lowercase().as_str() {
+                    "retracted publication" => UpdateStatus::Retracted,
+                    "expression of concern" => UpdateStatus::ExpressionOfConcern,
+                    "corrected and republished article" => UpdateStatus::Corrected,
+                    _ => return None,
+                };
+                Some((status, format!("pubmed publication type: {t}")))
+            })
+            .min_by_key(|(status, _)| *status)
+    }
+}
+
+/// Preprint-server details carried on a `SearchResult` from bioRxiv/medRxiv.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PreprintInfo {
+    /// `biorxiv` or `medrxiv`.
+    pub server: String,
+    /// The preprint's own DOI (`10.1101/...`).
+    pub doi: String,
+    pub version: Option<u32>,
+    /// DOI of the journal version, once the server reports one.
+    pub published_doi: Option<String>,
+    pub pdf_url: Option<String>,
+    pub jats_url: Option<String>,
+    pub license: Option<String>,
+}
+
+impl PreprintInfo {
+    /// Download locations for the preprint copy: the PDF, then the JATS XML.
+    pub fn locations(&self) -> Vec<OaLocation> {
+        let location = |url: &String, pdf: bool| OaLocation {
+            url: Some(url.clone()),
+            url_for_pdf: pdf.then(|| url.clone()),
+            license: self.license.clone(),
+            version: Some(OaVersion::Submitted),
+            host_type: Some(HostType::Repository),
+            evidence: Some(format!("{} api", self.server)),
+            is_best: pdf,
+        };
+        self.pdf_url
+            .iter()
+            .map(|u| location(u, true))
+            .chain(self.jats_url.iter().map(|u| location(u, false)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MeshHeading {
+    pub descriptor: String,
+    #[serde(default)]
+    pub qualifiers: Vec<String>,
+    #[serde(default)]
+    pub major_topic: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DocRecord {
     pub doc_id: String,
     pub doi: Option<String>,
     pub pmid: Option<String>,
+    #[serde(default)]
+    pub pmcid: Option<String>,
     pub title: String,
     pub journal: Option<String>,
     pub year: Option<i32>,
     pub authors: Vec<String>,
     pub abstract_text: Option<String>,
+    #[serde(default)]
+    pub mesh_headings: Vec<MeshHeading>,
+    #[serde(default)]
+    pub publication_types: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
     pub oa_status: OaStatus,
     pub oa_url: Option<String>,
+    #[serde(default)]
+    pub oa_locations: Vec<OaLocation>,
+    /// License of the copy that was (or will be) downloaded.
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Set while the only known version is a preprint.
+    #[serde(default)]
+    pub is_preprint: bool,
+    /// The preprint's DOI, kept after a journal version replaces `doi`.
+    #[serde(default)]
+    pub preprint_doi: Option<String>,
+    /// Retraction/correction found by `lit check-updates`.
+    #[serde(default)]
+    pub update_status: Option<UpdateStatus>,
+    /// Where the update came from: the notice DOI or the PubMed publication type.
+    #[serde(default)]
+    pub update_notice: Option<String>,
     pub epmc_id: Option<String>,
     pub local_pdf_path: Option<String>,
     pub local_xml_path: Option<String>,
@@ -36,6 +239,8 @@ pub struct DocRecord {
 pub struct DocIdentity {
     pub doi: Option<String>,
     pub pmid: Option<String>,
+    pub pmcid: Option<String>,
+    pub preprint_doi: Option<String>,
     pub title_hash: String,
     pub year: Option<i32>,
     pub sha256: Option<String>,
@@ -49,8 +254,17 @@ impl OaStatus {
         }
     }
 
+    /// Whether the status came from a location-level source such as Unpaywall.
+    pub fn is_detailed(&self) -> bool {
+        matches!(self, Self::Gold | Self::Green | Self::Hybrid | Self::Bronze)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
+            OaStatus::Gold => "gold",
+            OaStatus::Green => "green",
+            OaStatus::Hybrid => "hybrid",
+            OaStatus::Bronze => "bronze",
             OaStatus::Open => "open",
             OaStatus::Closed => "closed",
             OaStatus::Unknown => "unknown",
@@ -59,6 +273,10 @@ impl OaStatus {
 
     pub fn parse(raw: &str) -> Self {
         match raw {
+            "gold" => Self::Gold,
+            "green" => Self::Green,
+            "hybrid" => Self::Hybrid,
+            "bronze" => Self::Bronze,
             "open" => Self::Open,
             "closed" => Self::Closed,
             _ => Self::Unknown,
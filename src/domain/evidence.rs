@@ -19,6 +19,21 @@ pub enum ClaimType {
 pub enum AnchorType {
     Pdf,
     Xml,
+    Registry,
+}
+
+/// Locates a claim inside a ClinicalTrials.gov results section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegistryAnchor {
+    pub nct_id: String,
+    /// Results module the claim came from, e.g. `outcomeMeasuresModule` or `adverseEventsModule`.
+    pub module: String,
+    /// Position of the measure or event within the module, since titles can repeat.
+    #[serde(default)]
+    pub index: usize,
+    pub measure_title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_frame: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +50,8 @@ pub struct EvidenceLedgerRow {
     pub anchor_type: AnchorType,
     pub page_number: Option<u32>,
     pub section_heading: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryAnchor>,
     pub anchor_quote: String,
     pub claim_text: String,
     pub claim_type: ClaimType,
@@ -42,6 +59,20 @@ pub struct EvidenceLedgerRow {
     pub errors: Option<Vec<String>>,
 }
 
+impl RegistryAnchor {
+    /// `NCT… module[index] "title" (time frame)` for digests and rendered briefs.
+    pub fn label(&self) -> String {
+        let mut out = format!(
+            "{} {}[{}] \"{}\"",
+            self.nct_id, self.module, self.index, self.measure_title
+        );
+        if let Some(tf) = &self.time_frame {
+            out.push_str(&format!(" ({tf})"));
+        }
+        out
+    }
+}
+
 impl ClaimType {
     pub fn classify(sentence: &str) -> Self {
         let s = sentence.to_lowercase();
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// A MeSH descriptor as loaded from the NLM descriptor dump.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeshDescriptor {
+    pub ui: String,
+    pub name: String,
+    pub tree_numbers: Vec<String>,
+    /// Synonyms and variants, including the heading itself when the dump lists it.
+    pub entry_terms: Vec<String>,
+}
+
+/// Lookup keys for a heading or entry term: lowercased with whitespace collapsed, plus the
+/// reading-order form of NLM's inverted terms ("Aneurysm, Intracranial").
+pub fn term_keys(term: &str) -> Vec<String> {
+    let key = normalize_term(term);
+    let reading = normalize_term(&reading_order(term));
+    if reading == key {
+        vec![key]
+    } else {
+        vec![key, reading]
+    }
+}
+
+/// "Aneurysm, Intracranial" -> "Intracranial Aneurysm"; terms with no or several commas are
+/// returned as-is.
+pub fn reading_order(term: &str) -> String {
+    match term.split_once(',') {
+        Some((head, tail)) if !tail.contains(',') => format!("{} {}", tail.trim(), head.trim()),
+        _ => term.trim().to_string(),
+    }
+}
+
+pub fn normalize_term(term: &str) -> String {
+    term.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::term_keys;
+
+    #[test]
+    fn inverted_terms_get_reading_order_key() {
+        assert_eq!(
+            term_keys("Aneurysm,  Intracranial"),
+            vec!["aneurysm, intracranial", "intracranial aneurysm"]
+        );
+        assert_eq!(term_keys("Microsurgery"), vec!["microsurgery"]);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod doc;
 pub mod evidence;
 pub mod figure;
+pub mod mesh;
+pub mod trial;
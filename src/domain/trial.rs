@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrialIntervention {
+    pub kind: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrialOutcome {
+    pub measure: String,
+    pub time_frame: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Protocol summary for a ClinicalTrials.gov study; dates keep the registry's
+/// partial precision (`2023-04` or `2023-04-15`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialRecord {
+    pub nct_id: String,
+    pub doc_id: Option<String>,
+    pub title: String,
+    pub official_title: Option<String>,
+    pub phases: Vec<String>,
+    pub overall_status: Option<String>,
+    pub start_date: Option<String>,
+    pub completion_date: Option<String>,
+    pub enrollment: Option<u32>,
+    pub enrollment_type: Option<String>,
+    pub conditions: Vec<String>,
+    pub interventions: Vec<TrialIntervention>,
+    pub primary_outcomes: Vec<TrialOutcome>,
+    pub secondary_outcomes: Vec<TrialOutcome>,
+    pub sponsor: Option<String>,
+    pub has_results: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TrialRecord {
+    pub fn start_year(&self) -> Option<i32> {
+        self.start_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok())
+    }
+}
+
+/// A publication that reports or mentions a registered trial, and where the NCT id was found.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DocTrialLink {
+    pub doc_id: String,
+    pub nct_id: String,
+    /// `pubmed_databank`, `pdf` or `xml`.
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DocTrialLink {
+    /// Only a PubMed DataBank accession says the paper reports the trial; an NCT id found
+    /// in the full text may just be a citation, so `pdf`/`xml` links are mentions.
+    pub fn reports(&self) -> bool {
+        self.source == "pubmed_databank"
+    }
+}
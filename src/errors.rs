@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, LitError>;
@@ -26,4 +28,27 @@ pub enum LitError {
     External(String),
     #[error("pipeline error: {0}")]
     Pipeline(String),
+    /// `--offline` request with nothing cached: (source, url).
+    #[error("cache miss for {0} {1}")]
+    CacheMiss(String, String),
+    #[error("offline: {0}")]
+    Offline(String),
+    #[error("http status error: {0}")]
+    HttpStatus(Box<HttpStatusError>),
+}
+
+/// A non-success response, kept with its headers so retries can read `Retry-After`.
+#[derive(Debug, Clone)]
+pub struct HttpStatusError {
+    pub service: String,
+    pub url: String,
+    pub status: u16,
+    /// Lower-cased header names.
+    pub headers: BTreeMap<String, String>,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} returned status {} for {}", self.service, self.status, self.url)
+    }
 }
@@ -5,7 +5,7 @@ use crate::fs::hash::{sha256_bytes, sha256_file};
 use chrono::Utc;
 use roxmltree::Document;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 pub fn extract_jats_figures(doc: &DocRecord, xml_path: &Path, out_dir: &Path) -> Result<Vec<FigureIndexRow>> {
@@ -13,6 +13,8 @@ pub fn extract_jats_figures(doc: &DocRecord, xml_path: &Path, out_dir: &Path) ->
     let xml = Document::parse(&raw)?;
     fs::create_dir_all(out_dir)?;
 
+    let license = article_license(&xml).or_else(|| doc.license.clone());
+
     let mut rows = Vec::new();
     for fig in xml.descendants().filter(|n| n.has_tag_name("fig")) {
         let fig_id = fig.attribute("id").unwrap_or("unknown").to_string();
@@ -34,20 +36,26 @@ pub fn extract_jats_figures(doc: &DocRecord, xml_path: &Path, out_dir: &Path) ->
             .and_then(|n| n.attribute(("http://www.w3.org/1999/xlink", "href")))
             .map(ToString::to_string);
 
-        let target_name = format!("{}_{}.bin", doc.doc_id, fig_id);
-        let target_path = out_dir.join(target_name);
+        let xml_dir = xml_path.parent().unwrap_or_else(|| Path::new("."));
+        let graphic = graphic_href
+            .as_deref()
+            .and_then(|href| resolve_graphic(xml_dir, href));
+        let ext = graphic
+            .as_ref()
+            .and_then(|p| p.extension())
+            .and_then(|e| e.to_str())
+            .unwrap_or("bin")
+            .to_lowercase();
+        let target_path = out_dir.join(format!("{}_{}.{ext}", doc.doc_id, fig_id));
 
-        if let Some(href) = graphic_href {
-            let src = xml_path.parent().unwrap_or_else(|| Path::new(".")).join(href);
-            if src.exists() {
-                let bytes = fs::read(&src)?;
-                fs::write(&target_path, &bytes)?;
-            } else {
-                let fallback = caption.clone().unwrap_or_else(|| "missing graphic asset".to_string());
-                fs::write(&target_path, fallback.as_bytes())?;
-            }
+        if let Some(src) = &graphic {
+            let bytes = fs::read(src)?;
+            fs::write(&target_path, &bytes)?;
         } else {
-            let fallback = caption.clone().unwrap_or_else(|| "figure caption unavailable".to_string());
+            let fallback = match &graphic_href {
+                Some(_) => caption.clone().unwrap_or_else(|| "missing graphic asset".to_string()),
+                None => caption.clone().unwrap_or_else(|| "figure caption unavailable".to_string()),
+            };
             fs::write(&target_path, fallback.as_bytes())?;
         }
 
@@ -72,10 +80,48 @@ pub fn extract_jats_figures(doc: &DocRecord, xml_path: &Path, out_dir: &Path) ->
             width: None,
             height: None,
             sha256: sha,
-            license: None,
+            license: license.clone(),
             retrieved_at: Utc::now(),
         });
     }
 
     Ok(rows)
 }
+
+/// JATS `xlink:href` values usually omit the extension (`gr1`), while OA packages ship
+/// `gr1.jpg`; try the href as-is, then common image extensions.
+fn resolve_graphic(dir: &Path, href: &str) -> Option<PathBuf> {
+    let exact = dir.join(href);
+    if exact.is_file() {
+        return Some(exact);
+    }
+    ["jpg", "jpeg", "png", "gif", "tif", "tiff"]
+        .iter()
+        .map(|ext| dir.join(format!("{href}.{ext}")))
+        .find(|p| p.is_file())
+}
+
+/// Article-level license from `<permissions><license>`: the `xlink:href` when present,
+/// otherwise the `license-type` attribute.
+fn article_license(xml: &Document<'_>) -> Option<String> {
+    let node = xml.descendants().find(|n| n.has_tag_name("license"))?;
+    node.attribute(("http://www.w3.org/1999/xlink", "href"))
+        .or_else(|| node.attribute("license-type"))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_graphic;
+    use std::fs;
+
+    #[test]
+    fn resolves_extensionless_hrefs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(dir.path().join("gr1.jpg"), b"jpeg").expect("write");
+        assert_eq!(resolve_graphic(dir.path(), "gr1"), Some(dir.path().join("gr1.jpg")));
+        assert_eq!(resolve_graphic(dir.path(), "gr1.jpg"), Some(dir.path().join("gr1.jpg")));
+        assert_eq!(resolve_graphic(dir.path(), "gr2"), None);
+    }
+}
@@ -48,7 +48,7 @@ pub fn extract_pdf_figures(doc: &DocRecord, pdf_path: &Path, out_dir: &Path) ->
                 width: to_u32(image.width),
                 height: to_u32(image.height),
                 sha256: Some(sha256_bytes(image.content)),
-                license: None,
+                license: doc.license.clone(),
                 retrieved_at: Utc::now(),
             });
         }
@@ -5,4 +5,6 @@ pub mod figures_jats;
 pub mod figures_pdf;
 pub mod numbers;
 pub mod pdf_text;
+pub mod registry_results;
+pub mod trial_ids;
 pub mod xml_text;
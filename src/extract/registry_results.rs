@@ -0,0 +1,333 @@
+use crate::domain::doc::DocRecord;
+use crate::domain::evidence::{AnchorType, ClaimType, EvidenceLedgerRow, RegistryAnchor};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const OUTCOMES_MODULE: &str = "outcomeMeasuresModule";
+const ADVERSE_EVENTS_MODULE: &str = "adverseEventsModule";
+
+/// Converts a study's posted results into ledger rows, one per outcome measure and
+/// one per serious adverse event term. `local_path`/`sha256` describe the stored study JSON.
+pub fn results_to_claims(
+    doc: &DocRecord,
+    nct_id: &str,
+    study: &Value,
+    local_path: &str,
+    sha256: &str,
+) -> Vec<EvidenceLedgerRow> {
+    let results = &study["resultsSection"];
+    let mut out = Vec::new();
+
+    for (index, measure) in array(&results[OUTCOMES_MODULE]["outcomeMeasures"])
+        .iter()
+        .enumerate()
+    {
+        let Some(title) = text(&measure["title"]) else {
+            continue;
+        };
+        let values = outcome_values(measure);
+        if values.is_empty() {
+            continue;
+        }
+        let time_frame = text(&measure["timeFrame"]);
+        let quote = values.join("; ");
+
+        let mut claim = title.clone();
+        if let Some(tf) = &time_frame {
+            claim.push_str(&format!(" at {tf}"));
+        }
+        claim.push_str(&format!(": {quote}"));
+        let p_values = array(&measure["analyses"])
+            .iter()
+            .filter_map(|a| text(&a["pValue"]))
+            .collect::<Vec<_>>();
+        if !p_values.is_empty() {
+            claim.push_str(&format!(" (p={})", p_values.join(", ")));
+        }
+
+        let numbers = json!({
+            "type": measure["type"],
+            "param_type": measure["paramType"],
+            "unit": measure["unitOfMeasure"],
+            "groups": measure["groups"],
+            "classes": measure["classes"],
+            "analyses": measure["analyses"],
+        });
+        let anchor = RegistryAnchor {
+            nct_id: nct_id.to_string(),
+            module: OUTCOMES_MODULE.to_string(),
+            index,
+            measure_title: title,
+            time_frame,
+        };
+        out.push(row(
+            doc,
+            anchor,
+            quote,
+            claim,
+            ClaimType::Outcome,
+            numbers,
+            local_path,
+            sha256,
+        ));
+    }
+
+    let ae = &results[ADVERSE_EVENTS_MODULE];
+    let event_groups = group_titles(&ae["eventGroups"]);
+    for (index, event) in array(&ae["seriousEvents"]).iter().enumerate() {
+        let Some(term) = text(&event["term"]) else {
+            continue;
+        };
+        let counts = event_counts(event, &event_groups);
+        if counts.is_empty() {
+            continue;
+        }
+        let quote = counts.join("; ");
+        let claim = format!("Serious adverse event {term}: {quote}");
+        let numbers = json!({
+            "organ_system": event["organSystem"],
+            "stats": event["stats"],
+        });
+        let anchor = RegistryAnchor {
+            nct_id: nct_id.to_string(),
+            module: ADVERSE_EVENTS_MODULE.to_string(),
+            index,
+            measure_title: term,
+            time_frame: None,
+        };
+        out.push(row(
+            doc,
+            anchor,
+            quote,
+            claim,
+            ClaimType::Complication,
+            numbers,
+            local_path,
+            sha256,
+        ));
+    }
+
+    out
+}
+
+/// Re-reads the measure or event the anchor points at in the study JSON and checks that
+/// its title, time frame and posted values still match `quote`.
+pub fn verify_registry_quote(study: &Value, anchor: &RegistryAnchor, quote: &str) -> bool {
+    let results = &study["resultsSection"];
+    let rendered = match anchor.module.as_str() {
+        OUTCOMES_MODULE => {
+            let measure = &results[OUTCOMES_MODULE]["outcomeMeasures"][anchor.index];
+            if text(&measure["title"]).as_deref() != Some(anchor.measure_title.as_str())
+                || text(&measure["timeFrame"]) != anchor.time_frame
+            {
+                return false;
+            }
+            outcome_values(measure)
+        }
+        ADVERSE_EVENTS_MODULE => {
+            let ae = &results[ADVERSE_EVENTS_MODULE];
+            let event = &ae["seriousEvents"][anchor.index];
+            if text(&event["term"]).as_deref() != Some(anchor.measure_title.as_str()) {
+                return false;
+            }
+            event_counts(event, &group_titles(&ae["eventGroups"]))
+        }
+        _ => return false,
+    };
+    !quote.is_empty() && rendered.join("; ") == quote
+}
+
+/// `"<group>: <value> (<spread>) <unit>"` for every posted measurement.
+fn outcome_values(measure: &Value) -> Vec<String> {
+    let groups = group_titles(&measure["groups"]);
+    let unit = text(&measure["unitOfMeasure"]).unwrap_or_default();
+    array(&measure["classes"])
+        .iter()
+        .flat_map(|c| array(&c["categories"]))
+        .flat_map(|c| array(&c["measurements"]))
+        .filter_map(|m| {
+            let value = text(&m["value"])?;
+            let group = m["groupId"].as_str().unwrap_or_default();
+            let label = groups.get(group).cloned().unwrap_or_else(|| group.to_string());
+            let spread = text(&m["spread"])
+                .map(|s| format!(" ({s})"))
+                .or_else(|| {
+                    Some(format!(
+                        " ({} to {})",
+                        text(&m["lowerLimit"])?,
+                        text(&m["upperLimit"])?
+                    ))
+                })
+                .unwrap_or_default();
+            Some(format!("{label}: {value}{spread} {unit}").trim_end().to_string())
+        })
+        .collect()
+}
+
+/// `"<group>: <affected>/<at risk>"` for every group with counts.
+fn event_counts(event: &Value, event_groups: &HashMap<String, String>) -> Vec<String> {
+    array(&event["stats"])
+        .iter()
+        .filter_map(|s| {
+            let affected = s["numAffected"].as_u64()?;
+            let at_risk = s["numAtRisk"].as_u64()?;
+            let group = s["groupId"].as_str().unwrap_or_default();
+            let label = event_groups
+                .get(group)
+                .cloned()
+                .unwrap_or_else(|| group.to_string());
+            Some(format!("{label}: {affected}/{at_risk}"))
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn row(
+    doc: &DocRecord,
+    anchor: RegistryAnchor,
+    anchor_quote: String,
+    claim_text: String,
+    claim_type: ClaimType,
+    numbers: Value,
+    local_path: &str,
+    sha256: &str,
+) -> EvidenceLedgerRow {
+    EvidenceLedgerRow {
+        claim_id: format!("claim_{}", Uuid::new_v4()),
+        doc_id: doc.doc_id.clone(),
+        source_type: "registry".to_string(),
+        doi: doc.doi.clone(),
+        pmid: doc.pmid.clone(),
+        url: Some(format!(
+            "https://clinicaltrials.gov/study/{}?tab=results",
+            anchor.nct_id
+        )),
+        local_path: local_path.to_string(),
+        retrieved_at: Utc::now(),
+        sha256: Some(sha256.to_string()),
+        anchor_type: AnchorType::Registry,
+        page_number: None,
+        section_heading: None,
+        registry: Some(anchor),
+        anchor_quote,
+        claim_text,
+        claim_type,
+        numbers: Some(numbers),
+        errors: None,
+    }
+}
+
+fn array(v: &Value) -> Vec<Value> {
+    v.as_array().cloned().unwrap_or_default()
+}
+
+fn text(v: &Value) -> Option<String> {
+    v.as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+}
+
+fn group_titles(groups: &Value) -> HashMap<String, String> {
+    array(groups)
+        .iter()
+        .filter_map(|g| Some((g["id"].as_str()?.to_string(), text(&g["title"])?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{results_to_claims, verify_registry_quote};
+    use crate::domain::doc::DocRecord;
+    use crate::domain::evidence::AnchorType;
+    use serde_json::json;
+
+    fn doc() -> DocRecord {
+        DocRecord {
+            doc_id: "doc_1".into(),
+            title: "Trial".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_outcomes_and_serious_events() {
+        let study = json!({
+            "resultsSection": {
+                "outcomeMeasuresModule": {"outcomeMeasures": [{
+                    "type": "PRIMARY",
+                    "title": "Recurrence requiring surgery",
+                    "timeFrame": "6 months",
+                    "unitOfMeasure": "participants",
+                    "groups": [{"id": "OG000", "title": "TXA"}, {"id": "OG001", "title": "Placebo"}],
+                    "classes": [{"categories": [{"measurements": [
+                        {"groupId": "OG000", "value": "12"},
+                        {"groupId": "OG001", "value": "21"}
+                    ]}]}],
+                    "analyses": [{"pValue": "0.04"}]
+                }]},
+                "adverseEventsModule": {
+                    "eventGroups": [{"id": "EG000", "title": "TXA"}],
+                    "seriousEvents": [{"term": "Seizure", "stats": [
+                        {"groupId": "EG000", "numAffected": 2, "numAtRisk": 120}
+                    ]}]
+                }
+            }
+        });
+
+        let rows = results_to_claims(&doc(), "NCT01234567", &study, "study.json", "abc");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].anchor_type, AnchorType::Registry);
+        assert_eq!(
+            rows[0].claim_text,
+            "Recurrence requiring surgery at 6 months: TXA: 12 participants; Placebo: 21 participants (p=0.04)"
+        );
+        assert_eq!(
+            rows[0].anchor_quote,
+            "TXA: 12 participants; Placebo: 21 participants"
+        );
+        let anchor = rows[1].registry.as_ref().expect("registry anchor");
+        assert_eq!(anchor.module, "adverseEventsModule");
+        assert_eq!(anchor.measure_title, "Seizure");
+        assert_eq!(rows[1].claim_text, "Serious adverse event Seizure: TXA: 2/120");
+        assert_eq!(rows[1].anchor_quote, "TXA: 2/120");
+
+        for row in &rows {
+            let anchor = row.registry.as_ref().expect("registry anchor");
+            assert!(verify_registry_quote(&study, anchor, &row.anchor_quote));
+        }
+        let outcome = rows[0].registry.as_ref().expect("registry anchor");
+        assert!(!verify_registry_quote(&study, outcome, "TXA: 10 participants"));
+    }
+
+    #[test]
+    fn repeated_measure_titles_get_distinct_anchors() {
+        let measure = |tf: &str, txa: &str| {
+            json!({
+                "title": "Recurrence",
+                "timeFrame": tf,
+                "groups": [{"id": "OG000", "title": "TXA"}],
+                "classes": [{"categories": [{"measurements": [
+                    {"groupId": "OG000", "value": txa}
+                ]}]}]
+            })
+        };
+        let study = json!({
+            "resultsSection": {"outcomeMeasuresModule": {"outcomeMeasures": [
+                measure("3 months", "5"),
+                measure("6 months", "9")
+            ]}}
+        });
+
+        let rows = results_to_claims(&doc(), "NCT01234567", &study, "study.json", "abc");
+        let first = rows[0].registry.as_ref().expect("registry anchor");
+        let second = rows[1].registry.as_ref().expect("registry anchor");
+        assert_ne!(first, second);
+        assert_eq!(second.index, 1);
+        assert_eq!(second.time_frame.as_deref(), Some("6 months"));
+        assert!(!verify_registry_quote(&study, first, &rows[1].anchor_quote));
+        assert!(verify_registry_quote(&study, second, &rows[1].anchor_quote));
+    }
+}
@@ -0,0 +1,29 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeSet;
+
+/// NCT ids as printed in papers, tolerating a space or hyphen after the prefix ("NCT 01234567").
+static NCT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bNCT[\s-]?(\d{8})\b").expect("regex compiles"));
+
+/// Distinct ClinicalTrials.gov ids mentioned in `text`, normalized to `NCT########`.
+pub fn find_nct_ids(text: &str) -> BTreeSet<String> {
+    NCT_RE
+        .captures_iter(text)
+        .filter_map(|cap| cap.get(1))
+        .map(|digits| format!("NCT{}", digits.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_nct_ids;
+
+    #[test]
+    fn finds_and_normalizes_nct_ids() {
+        let text = "Registered at ClinicalTrials.gov (NCT01234567; nct 07654321), see also NCT01234567.";
+        let ids = find_nct_ids(text).into_iter().collect::<Vec<_>>();
+        assert_eq!(ids, vec!["NCT01234567", "NCT07654321"]);
+        assert!(find_nct_ids("NCT123 and XNCT01234567").is_empty());
+    }
+}
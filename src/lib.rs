@@ -10,4 +10,5 @@ pub mod extract;
 pub mod fs;
 pub mod net;
 pub mod pipeline;
+pub mod query;
 pub mod types;
@@ -7,7 +7,10 @@ use lit::pipeline;
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    let config = AppConfig::load(cli.config.as_deref(), cli.data_dir.as_deref())?;
+    let mut config = AppConfig::load(cli.config.as_deref(), cli.data_dir.as_deref())?;
+    if cli.offline {
+        config.network.offline = true;
+    }
     config.ensure_layout()?;
     tracing_subscriber::fmt()
         .with_env_filter(cli.log_level.clone())
@@ -20,6 +23,9 @@ async fn main() -> Result<()> {
     match cli.command {
         Commands::Search(args) => pipeline::search::run(&app, args).await?,
         Commands::Fetch(args) => pipeline::metadata::run(&app, args).await?,
+        Commands::ReconcileIds(args) => pipeline::reconcile::run(&app, args).await?,
+        Commands::CheckUpdates(args) => pipeline::updates::run(&app, args).await?,
+        Commands::Snowball(args) => pipeline::snowball::run(&app, args).await?,
         Commands::DownloadOa(args) => pipeline::download_oa::run(&app, args).await?,
         Commands::IngestLocal(args) => pipeline::ingest_local::run(&app, args).await?,
         Commands::Extract(args) => pipeline::extract::run(&app, args).await?,
@@ -27,6 +33,10 @@ async fn main() -> Result<()> {
         Commands::Brief(args) => pipeline::synthesis::run_brief(&app, args).await?,
         Commands::Qa(args) => pipeline::qa::run(&app, args).await?,
         Commands::Run(args) => pipeline::run::run(&app, args).await?,
+        Commands::Sources(args) => pipeline::sources::run(&app, args).await?,
+        Commands::Docs(args) => pipeline::docs::run(&app, args).await?,
+        Commands::Mesh(args) => pipeline::mesh::run(&app, args).await?,
+        Commands::Cache(args) => pipeline::cache::run(&app, args).await?,
     }
 
     Ok(())
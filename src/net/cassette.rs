@@ -0,0 +1,124 @@
+use crate::config::CassetteMode;
+use crate::errors::{LitError, Result};
+use crate::fs::hash::sha256_bytes;
+use crate::net::url::redact_url;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A response as seen on the wire, before any status handling.
+#[derive(Debug, Clone)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// Metadata for one recorded request; the body is stored verbatim next to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    source: String,
+    method: String,
+    url: String,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body_file: String,
+    recorded_at: DateTime<Utc>,
+}
+
+/// `manifest.json` in the cassette directory, rewritten by every recording session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    recorded_on: NaiveDate,
+}
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Record/replay store for `ApiClient` traffic: one `<key>.json` + `<key>.body` pair per
+/// (source, URL), where the key ignores redacted credentials.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    dir: PathBuf,
+    mode: CassetteMode,
+    recorded_on: Option<NaiveDate>,
+}
+
+impl Cassette {
+    pub fn new(dir: &Path, mode: CassetteMode) -> Result<Self> {
+        let manifest = dir.join(MANIFEST_FILE);
+        let recorded_on = match mode {
+            CassetteMode::Record => {
+                fs::create_dir_all(dir)?;
+                let today = Utc::now().date_naive();
+                let manifest_json = serde_json::to_string_pretty(&Manifest { recorded_on: today })?;
+                fs::write(&manifest, manifest_json)?;
+                Some(today)
+            }
+            _ if manifest.exists() => {
+                let saved: Manifest = serde_json::from_str(&fs::read_to_string(&manifest)?)?;
+                Some(saved.recorded_on)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            mode,
+            recorded_on,
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Day of the latest recording session; `None` for cassettes recorded without a manifest.
+    pub fn recorded_on(&self) -> Option<NaiveDate> {
+        self.recorded_on
+    }
+
+    /// Saves (or overwrites) the interaction for `source`/`url`; after retries the last
+    /// response wins.
+    pub fn record(&self, source: &str, url: &str, response: &RecordedResponse) -> Result<()> {
+        let key = key_for(source, url);
+        let body_file = format!("{key}.body");
+        fs::write(self.dir.join(&body_file), &response.body)?;
+        let interaction = Interaction {
+            source: source.to_string(),
+            method: "GET".to_string(),
+            url: redact_url(url),
+            status: response.status,
+            headers: response.headers.clone(),
+            body_file,
+            recorded_at: Utc::now(),
+        };
+        fs::write(
+            self.dir.join(format!("{key}.json")),
+            serde_json::to_string_pretty(&interaction)?,
+        )?;
+        Ok(())
+    }
+
+    pub fn replay(&self, source: &str, url: &str) -> Result<RecordedResponse> {
+        let key = key_for(source, url);
+        let meta = self.dir.join(format!("{key}.json"));
+        if !meta.exists() {
+            return Err(LitError::NotFound(format!(
+                "no cassette entry for {source} {} in {}",
+                redact_url(url),
+                self.dir.display()
+            )));
+        }
+        let interaction: Interaction = serde_json::from_str(&fs::read_to_string(meta)?)?;
+        Ok(RecordedResponse {
+            status: interaction.status,
+            headers: interaction.headers,
+            body: fs::read(self.dir.join(&interaction.body_file))?,
+        })
+    }
+}
+
+fn key_for(source: &str, url: &str) -> String {
+    sha256_bytes(format!("GET|{source}|{}", redact_url(url)).as_bytes())
+}
@@ -1,2 +1,4 @@
+pub mod cassette;
 pub mod ratelimit;
 pub mod retry;
+pub mod url;
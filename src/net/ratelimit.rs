@@ -7,9 +7,11 @@ use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 
+type DirectLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
 #[derive(Clone)]
 pub struct RateLimitRegistry {
-    map: Arc<HashMap<String, Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>,
+    map: Arc<HashMap<String, Arc<DirectLimiter>>>,
 }
 
 impl RateLimitRegistry {
@@ -21,6 +23,9 @@ impl RateLimitRegistry {
         map.insert("openalex".to_string(), new_limiter(cfg.openalex_per_sec));
         map.insert("unpaywall".to_string(), new_limiter(cfg.unpaywall_per_sec));
         map.insert("clinicaltrials".to_string(), new_limiter(cfg.clinicaltrials_per_sec));
+        map.insert("idconv".to_string(), new_limiter(cfg.idconv_per_sec));
+        map.insert("pmc_oa".to_string(), new_limiter(cfg.pmc_oa_per_sec));
+        map.insert("biorxiv".to_string(), new_limiter(cfg.biorxiv_per_sec));
         Self { map: Arc::new(map) }
     }
 
@@ -31,7 +36,7 @@ impl RateLimitRegistry {
     }
 }
 
-fn new_limiter(per_sec: u32) -> Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
+fn new_limiter(per_sec: u32) -> Arc<DirectLimiter> {
     let nz = NonZeroU32::new(per_sec.max(1)).expect("nonzero");
     Arc::new(RateLimiter::direct(Quota::per_second(nz)))
 }
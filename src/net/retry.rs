@@ -1,5 +1,6 @@
 use crate::config::RetryConfig;
-use crate::errors::{LitError, Result};
+use crate::errors::{HttpStatusError, LitError, Result};
+use chrono::{DateTime, Utc};
 use std::future::Future;
 use tokio::time::{Duration, sleep};
 
@@ -9,7 +10,6 @@ where
     Fut: Future<Output = Result<T>>,
 {
     let mut attempt = 0usize;
-    let mut delay = cfg.base_delay_ms;
     loop {
         attempt += 1;
         match task().await {
@@ -18,24 +18,126 @@ where
                 if attempt >= cfg.max_attempts || !is_retryable(&err) {
                     return Err(err);
                 }
-                let jitter = (attempt as u64 * 37) % 100;
-                sleep(Duration::from_millis((delay + jitter).min(cfg.max_delay_ms))).await;
-                delay = (delay * 2).min(cfg.max_delay_ms);
+                let Some(delay) = next_delay(cfg, attempt, &err, Utc::now()) else {
+                    return Err(err);
+                };
+                tracing::debug!(attempt, delay_ms = delay.as_millis() as u64, error = %err, "retrying");
+                sleep(delay).await;
             }
         }
     }
 }
 
+/// Transient failures only: timeouts, dropped connections, 408, 429 and 5xx other than 501.
 fn is_retryable(err: &LitError) -> bool {
     match err {
         LitError::Http(inner) => {
             inner.is_timeout()
                 || inner.is_connect()
-                || inner.status().is_some_and(|s| {
-                    s.as_u16() == 429 || (500..=599).contains(&s.as_u16())
-                })
+                || inner.is_body()
+                || inner
+                    .status()
+                    .is_some_and(|s| is_transient_status(s.as_u16()))
         }
-        LitError::External(_) => true,
+        LitError::HttpStatus(status) => is_transient_status(status.status),
         _ => false,
     }
 }
+
+fn is_transient_status(status: u16) -> bool {
+    matches!(status, 408 | 429) || ((500..=599).contains(&status) && status != 501)
+}
+
+/// Exponential backoff with equal jitter (half fixed, half random), stretched to any
+/// `Retry-After` the server sent. `None` when that wait exceeds `max_retry_after_ms`.
+fn next_delay(
+    cfg: &RetryConfig,
+    attempt: usize,
+    err: &LitError,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let exp = cfg
+        .base_delay_ms
+        .saturating_mul(1u64 << (attempt - 1).min(20))
+        .min(cfg.max_delay_ms);
+    let backoff = Duration::from_millis(exp / 2 + fastrand::u64(0..=exp - exp / 2));
+    let requested = match err {
+        LitError::HttpStatus(status) => retry_after(status, now),
+        _ => None,
+    };
+    match requested {
+        Some(wait) if wait > Duration::from_millis(cfg.max_retry_after_ms) => None,
+        Some(wait) => Some(wait.max(backoff)),
+        None => Some(backoff),
+    }
+}
+
+/// `Retry-After` as delay-seconds or an HTTP date.
+fn retry_after(err: &HttpStatusError, now: DateTime<Utc>) -> Option<Duration> {
+    let value = err.headers.get("retry-after")?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_retryable, next_delay};
+    use crate::config::RetryConfig;
+    use crate::errors::{HttpStatusError, LitError};
+    use chrono::{TimeZone, Utc};
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::Duration;
+
+    fn status(code: u16, retry_after: Option<&str>) -> LitError {
+        LitError::HttpStatus(Box::new(HttpStatusError {
+            service: "crossref".to_string(),
+            url: "https://api.crossref.org/works".to_string(),
+            status: code,
+            headers: retry_after
+                .map(|v| BTreeMap::from([("retry-after".to_string(), v.to_string())]))
+                .unwrap_or_default(),
+        }))
+    }
+
+    #[test]
+    fn classifies_statuses_and_honours_retry_after() {
+        assert!(is_retryable(&status(429, None)));
+        assert!(is_retryable(&status(503, None)));
+        assert!(!is_retryable(&status(404, None)));
+        assert!(!is_retryable(&status(501, None)));
+        assert!(!is_retryable(&LitError::External("bad json".to_string())));
+
+        let cfg = RetryConfig {
+            max_attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 1000,
+            max_retry_after_ms: 30_000,
+            sources: HashMap::new(),
+        };
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        for attempt in 1..=6 {
+            let delay = next_delay(&cfg, attempt, &status(500, None), now).unwrap();
+            let cap = (100u64 << (attempt - 1)).min(1000);
+            assert!(delay >= Duration::from_millis(cap / 2) && delay <= Duration::from_millis(cap));
+        }
+        assert_eq!(
+            next_delay(&cfg, 1, &status(429, Some("7")), now),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            next_delay(
+                &cfg,
+                1,
+                &status(503, Some("Wed, 21 Oct 2015 07:28:20 GMT")),
+                now
+            ),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(next_delay(&cfg, 1, &status(429, Some("120")), now), None);
+    }
+}
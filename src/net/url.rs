@@ -0,0 +1,36 @@
+/// Query parameters carrying credentials; masked so cassettes and cache entries can be shared
+/// and reused under a different config.
+const REDACTED_PARAMS: &[&str] = &["api_key", "email", "mailto"];
+
+/// Replaces the values of credential query parameters with `REDACTED`.
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if REDACTED_PARAMS.contains(&name) => format!("{name}=REDACTED"),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>();
+    format!("{base}?{}", params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact_url;
+
+    #[test]
+    fn credentials_are_redacted() {
+        assert_eq!(
+            redact_url("https://api.unpaywall.org/v2/10.1/x?email=me@example.org"),
+            "https://api.unpaywall.org/v2/10.1/x?email=REDACTED"
+        );
+        assert_eq!(
+            redact_url("https://e.utils/esearch.fcgi?db=pubmed&api_key=abc&term=x"),
+            "https://e.utils/esearch.fcgi?db=pubmed&api_key=REDACTED&term=x"
+        );
+        assert_eq!(redact_url("https://a/b"), "https://a/b");
+    }
+}
@@ -1,6 +1,9 @@
 use crate::config::AppConfig;
+use crate::connectors::registry::ConnectorRegistry;
 use crate::connectors::ApiClient;
 use crate::db::docs_repo::DocsRepo;
+use crate::db::mesh_repo::MeshRepo;
+use crate::db::trials_repo::TrialsRepo;
 use crate::db::Db;
 use crate::errors::Result;
 use crate::fs::layout::Paths;
@@ -11,7 +14,10 @@ pub struct App {
     pub paths: Paths,
     pub db: Db,
     pub docs: DocsRepo,
+    pub trials: TrialsRepo,
+    pub mesh: MeshRepo,
     pub api: ApiClient,
+    pub connectors: ConnectorRegistry,
 }
 
 impl App {
@@ -19,13 +25,19 @@ impl App {
         let paths = Paths::new(&config);
         let db = Db::open(&config.data.sqlite_path)?;
         let docs = DocsRepo::new(db.clone());
+        let trials = TrialsRepo::new(db.clone());
+        let mesh = MeshRepo::new(db.clone());
         let api = ApiClient::new(&config)?;
+        let connectors = ConnectorRegistry::from_config(&config);
         Ok(Self {
             config,
             paths,
             db,
             docs,
+            trials,
+            mesh,
             api,
+            connectors,
         })
     }
 }
@@ -0,0 +1,35 @@
+use crate::cli::{CacheArgs, CacheCommand};
+use crate::errors::Result;
+use crate::pipeline::app::App;
+
+pub async fn run(app: &App, args: CacheArgs) -> Result<()> {
+    let cache = app.api.cache();
+    match args.command {
+        CacheCommand::Stats { source } => {
+            let stats = cache.stats(source.as_deref())?;
+            println!(
+                "{:<16} {:>8} {:>12} {:>8} {:>10}",
+                "SOURCE", "ENTRIES", "BYTES", "EXPIRED", "TTL_SECS"
+            );
+            for (name, s) in &stats {
+                println!(
+                    "{:<16} {:>8} {:>12} {:>8} {:>10}",
+                    name,
+                    s.entries,
+                    s.bytes,
+                    s.expired,
+                    cache.ttl_for(name)
+                );
+            }
+        }
+        CacheCommand::Prune { source } => {
+            let (removed, bytes) = cache.prune(source.as_deref())?;
+            println!("pruned {removed} expired entries ({bytes} bytes)");
+        }
+        CacheCommand::Clear { source } => {
+            let (removed, bytes) = cache.clear(source.as_deref())?;
+            println!("cleared {removed} entries ({bytes} bytes)");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use crate::cli::{DocsArgs, DocsCommand};
+use crate::domain::doc::DocRecord;
+use crate::domain::trial::{DocTrialLink, TrialRecord};
+use crate::errors::{LitError, Result};
+use crate::pipeline::app::App;
+
+pub async fn run(app: &App, args: DocsArgs) -> Result<()> {
+    match args.command {
+        DocsCommand::Show { id } => show(app, id.trim()),
+    }
+}
+
+fn show(app: &App, id: &str) -> Result<()> {
+    if id.to_uppercase().starts_with("NCT") {
+        let trial = app
+            .trials
+            .get(id)?
+            .ok_or_else(|| LitError::NotFound(format!("trial {id}")))?;
+        print_trial(app, &trial)?;
+        return Ok(());
+    }
+
+    let doc_id = app.docs.resolve_doc_id(id)?;
+    if doc_id != id {
+        println!("{id} was merged into {doc_id}");
+    }
+    let doc = app
+        .docs
+        .get_doc(&doc_id)?
+        .ok_or_else(|| LitError::NotFound(format!("doc {id}")))?;
+    print_doc(&doc);
+    for note in app.docs.provenance(&doc.doc_id)? {
+        println!("found via: {note}");
+    }
+
+    if let Some(trial) = app.trials.for_doc(&doc.doc_id)? {
+        println!();
+        print_trial(app, &trial)?;
+    }
+
+    let (reports, mentions): (Vec<_>, Vec<_>) = app
+        .trials
+        .links_for_doc(&doc.doc_id)?
+        .into_iter()
+        .partition(DocTrialLink::reports);
+    println!("\nreports trials:");
+    if reports.is_empty() {
+        println!("  (none detected)");
+    }
+    for link in reports {
+        print_trial_link(app, &link)?;
+    }
+    if !mentions.is_empty() {
+        println!("mentions trials:");
+        for link in mentions {
+            print_trial_link(app, &link)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_trial_link(app: &App, link: &DocTrialLink) -> Result<()> {
+    let label = app
+        .trials
+        .get(&link.nct_id)?
+        .map(|t| format!("{} [{}]", t.title, t.overall_status.unwrap_or_default()))
+        .unwrap_or_else(|| "(not in local registry)".to_string());
+    println!("  {} via {}: {}", link.nct_id, link.source, label);
+    Ok(())
+}
+
+fn print_doc(doc: &DocRecord) {
+    println!("doc_id:    {}", doc.doc_id);
+    println!("title:     {}", doc.title);
+    println!("journal:   {}", doc.journal.as_deref().unwrap_or("-"));
+    println!(
+        "year:      {}",
+        doc.year.map(|y| y.to_string()).unwrap_or_else(|| "-".to_string())
+    );
+    println!("doi:       {}", doc.doi.as_deref().unwrap_or("-"));
+    println!("pmid:      {}", doc.pmid.as_deref().unwrap_or("-"));
+    println!("pmcid:     {}", doc.pmcid.as_deref().unwrap_or("-"));
+    if let Some(preprint_doi) = &doc.preprint_doi {
+        let label = if doc.is_preprint { "preprint only" } else { "journal version known" };
+        println!("preprint:  {preprint_doi} ({label})");
+    }
+    println!("oa_status: {}", doc.oa_status.as_str());
+    if let Some(status) = &doc.update_status {
+        println!(
+            "update:    {} ({})",
+            status.as_str(),
+            doc.update_notice.as_deref().unwrap_or("no notice recorded")
+        );
+    }
+    if let Some(license) = &doc.license {
+        println!("license:   {license}");
+    }
+    if let Some(path) = doc.local_pdf_path.as_ref().or(doc.local_xml_path.as_ref()) {
+        println!("local:     {path}");
+    }
+}
+
+fn print_trial(app: &App, trial: &TrialRecord) -> Result<()> {
+    println!("trial:     {} {}", trial.nct_id, trial.title);
+    println!(
+        "status:    {} phase={} enrollment={}",
+        trial.overall_status.as_deref().unwrap_or("unknown"),
+        if trial.phases.is_empty() {
+            "N/A".to_string()
+        } else {
+            trial.phases.join("/")
+        },
+        trial
+            .enrollment
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    );
+    let (publications, mentions): (Vec<_>, Vec<_>) = app
+        .trials
+        .links_for_trial(&trial.nct_id)?
+        .into_iter()
+        .partition(DocTrialLink::reports);
+    println!("publications:");
+    if publications.is_empty() {
+        println!("  (no linked publication yet)");
+    }
+    for link in publications {
+        print_doc_link(app, &link)?;
+    }
+    if !mentions.is_empty() {
+        println!("mentioned in:");
+        for link in mentions {
+            print_doc_link(app, &link)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_doc_link(app: &App, link: &DocTrialLink) -> Result<()> {
+    let title = app
+        .docs
+        .get_doc(&link.doc_id)?
+        .map(|d| d.title)
+        .unwrap_or_default();
+    println!("  {} via {}: {}", link.doc_id, link.source, title);
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use crate::cli::DownloadOaArgs;
-use crate::errors::Result;
+use crate::connectors::{epmc, pmc_oa};
+use crate::domain::doc::{sort_locations, DocRecord};
+use crate::errors::{LitError, Result};
 use crate::fs::hash::sha256_bytes;
 use crate::pipeline::app::App;
 use std::fs;
@@ -24,32 +26,67 @@ pub async fn run(app: &App, args: DownloadOaArgs) -> Result<()> {
         docs.retain(|d| ids.contains(&d.doc_id));
     }
 
+    // Downloads are not cached, so offline there is nothing to fetch; report what was skipped.
+    if app.api.is_offline() {
+        for doc in &docs {
+            tracing::info!(doc_id = %doc.doc_id, "offline: OA download skipped");
+        }
+        println!("offline: skipped OA download for {} docs", docs.len());
+        return Ok(());
+    }
+
     let mut downloaded = 0usize;
     for doc in docs {
-        let url = if let Some(oa) = &doc.oa_url {
-            oa.clone()
-        } else if let Some(epmc_id) = &doc.epmc_id {
-            format!(
-                "https://www.ebi.ac.uk/europepmc/webservices/rest/{}/fullTextXML",
-                epmc_id
-            )
-        } else {
-            continue;
-        };
+        if let Some(pmcid) = &doc.pmcid {
+            match fetch_pmc_package(app, &doc, pmcid).await {
+                Ok(true) => {
+                    downloaded += 1;
+                    continue;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        doc_id = %doc.doc_id,
+                        pmcid = %pmcid,
+                        error = %err,
+                        "PMC OA package download failed, trying other locations"
+                    );
+                }
+            }
+        }
 
-        let source = source_for_url(&url);
-        let bytes = match app.api.download_bytes(source, &url).await {
-            Ok(bytes) => bytes,
-            Err(err) => {
-                tracing::warn!(
-                    doc_id = %doc.doc_id,
-                    source,
-                    url = %url,
-                    error = %err,
-                    "skipping OA download for document"
-                );
-                continue;
+        let mut fetched = None;
+        let epmc_base = &app.api.endpoints().europepmc;
+        for (url, license) in candidate_urls(&doc, epmc_base) {
+            let source = source_for_url(&url, epmc_base);
+            match app.api.download_bytes(source, &url).await {
+                // Landing pages answer 200 with HTML; only a PDF or XML body is a download.
+                Ok(bytes) if looks_like_pdf(&bytes) || looks_like_xml(&bytes) => {
+                    fetched = Some((bytes, license));
+                    break;
+                }
+                Ok(_) => {
+                    tracing::warn!(
+                        doc_id = %doc.doc_id,
+                        source,
+                        url = %url,
+                        "OA location returned neither PDF nor XML, trying next location"
+                    );
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        doc_id = %doc.doc_id,
+                        source,
+                        url = %url,
+                        error = %err,
+                        "OA download failed, trying next location"
+                    );
+                }
             }
+        }
+        let Some((bytes, license)) = fetched else {
+            tracing::warn!(doc_id = %doc.doc_id, "skipping OA download for document");
+            continue;
         };
 
         let ext = if looks_like_xml(&bytes) { "xml" } else { "pdf" };
@@ -72,6 +109,9 @@ pub async fn run(app: &App, args: DownloadOaArgs) -> Result<()> {
 
         app.docs
             .update_local_paths(&doc.doc_id, pdf_path, xml_path, Some(sha))?;
+        if let Some(license) = license {
+            app.docs.set_license(&doc.doc_id, &license)?;
+        }
         downloaded += 1;
     }
 
@@ -79,13 +119,87 @@ pub async fn run(app: &App, args: DownloadOaArgs) -> Result<()> {
     Ok(())
 }
 
+/// Downloads and unpacks the PMC OA package (JATS XML, images, supplementary files) into
+/// `oa/<doc_id>/`. Returns `false` when the article is not in the OA subset.
+async fn fetch_pmc_package(app: &App, doc: &DocRecord, pmcid: &str) -> Result<bool> {
+    let Some(package) = pmc_oa::lookup(&app.api, pmcid).await? else {
+        return Ok(false);
+    };
+    let Some(tgz_url) = &package.tgz_url else {
+        return Ok(false);
+    };
+    let bytes = app.api.download_bytes("pmc_oa", tgz_url).await?;
+    let dir = app.paths.oa_doc_dir(&doc.doc_id);
+    let unpacked = pmc_oa::unpack_package(&bytes, &dir)?;
+    let xml_path = unpacked.xml_path.ok_or_else(|| {
+        LitError::External(format!("OA package for {pmcid} has no article XML"))
+    })?;
+
+    let sha = sha256_bytes(&fs::read(&xml_path)?);
+    app.docs.update_local_paths(
+        &doc.doc_id,
+        None,
+        Some(xml_path.to_string_lossy().to_string()),
+        Some(sha),
+    )?;
+    if let Some(license) = package.license.or_else(|| doc.license.clone()) {
+        app.docs.set_license(&doc.doc_id, &license)?;
+    }
+    tracing::info!(
+        doc_id = %doc.doc_id,
+        pmcid = %pmcid,
+        files = unpacked.files,
+        "unpacked PMC OA package"
+    );
+    Ok(true)
+}
+
+/// Download URLs in preference order: direct PDF links of the Unpaywall locations (version of
+/// record first), then the stored `oa_url`, then Europe PMC full text, and only then the
+/// locations' landing pages. Europe PMC links go to `epmc_base`. Each carries its location's
+/// license.
+fn candidate_urls(doc: &DocRecord, epmc_base: &str) -> Vec<(String, Option<String>)> {
+    fn push(out: &mut Vec<(String, Option<String>)>, url: &str, license: &Option<String>) {
+        if !out.iter().any(|(u, _)| u == url) {
+            out.push((url.to_string(), license.clone()));
+        }
+    }
+
+    let mut locations = doc.oa_locations.clone();
+    sort_locations(&mut locations);
+    let mut out: Vec<(String, Option<String>)> = Vec::new();
+    for loc in &locations {
+        if let Some(url) = &loc.url_for_pdf {
+            push(&mut out, url, &loc.license);
+        }
+    }
+    if let Some(oa) = &doc.oa_url {
+        push(&mut out, &epmc::rebase_url(oa, epmc_base), &doc.license);
+    }
+    if let Some(epmc_id) = &doc.epmc_id {
+        push(&mut out, &epmc::full_text_url(epmc_base, epmc_id), &doc.license);
+    }
+    for loc in &locations {
+        if let Some(url) = &loc.url {
+            push(&mut out, url, &loc.license);
+        }
+    }
+    out
+}
+
+fn looks_like_pdf(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(1024)]
+        .windows(5)
+        .any(|w| w == b"%PDF-")
+}
+
 fn looks_like_xml(bytes: &[u8]) -> bool {
     let preview = String::from_utf8_lossy(&bytes[..bytes.len().min(200)]).to_lowercase();
-    preview.contains("<?xml") || preview.contains("<article")
+    !preview.contains("<html") && (preview.contains("<?xml") || preview.contains("<article"))
 }
 
-fn source_for_url(url: &str) -> &'static str {
-    if url.contains("europepmc") {
+fn source_for_url(url: &str, epmc_base: &str) -> &'static str {
+    if url.starts_with(epmc_base) || url.contains("europepmc") {
         "europepmc"
     } else if url.contains("crossref") {
         "crossref"
@@ -95,3 +209,75 @@ fn source_for_url(url: &str) -> &'static str {
         "unpaywall"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{candidate_urls, looks_like_pdf, looks_like_xml};
+    use crate::connectors::epmc;
+    use crate::domain::doc::{DocRecord, OaLocation, OaVersion};
+
+    fn doc(oa_locations: Vec<OaLocation>) -> DocRecord {
+        DocRecord {
+            doc_id: "doc_1".into(),
+            doi: Some("10.1000/x".into()),
+            title: "Paper".into(),
+            oa_locations,
+            epmc_id: Some("PMC1".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn landing_pages_come_after_every_direct_link() {
+        let landing_only = OaLocation {
+            url: Some("https://publisher.example/article/1".into()),
+            url_for_pdf: None,
+            license: Some("cc-by".into()),
+            version: Some(OaVersion::Published),
+            host_type: None,
+            evidence: None,
+            is_best: true,
+        };
+        let repository = OaLocation {
+            url: Some("https://repo.example/record/1".into()),
+            url_for_pdf: Some("https://repo.example/record/1/file.pdf".into()),
+            license: Some("cc-by-nc".into()),
+            version: Some(OaVersion::Accepted),
+            host_type: None,
+            evidence: None,
+            is_best: false,
+        };
+        let urls = candidate_urls(&doc(vec![landing_only, repository]), "https://epmc.example")
+            .into_iter()
+            .map(|(url, _)| url)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://repo.example/record/1/file.pdf",
+                "https://epmc.example/PMC1/fullTextXML",
+                "https://publisher.example/article/1",
+                "https://repo.example/record/1",
+            ]
+        );
+    }
+
+    #[test]
+    fn html_bodies_are_neither_pdf_nor_xml() {
+        let html = b"<!DOCTYPE html><html><body><article>Abstract</article></body></html>";
+        assert!(!looks_like_pdf(html) && !looks_like_xml(html));
+        assert!(looks_like_pdf(b"%PDF-1.7\n%\xe2\xe3"));
+        assert!(looks_like_xml(b"<?xml version=\"1.0\"?><article/>"));
+    }
+
+    #[test]
+    fn stored_europe_pmc_links_go_to_the_configured_base() {
+        let stored = DocRecord {
+            oa_url: Some(epmc::full_text_url(epmc::CANONICAL_BASE, "PMC1")),
+            ..doc(vec![])
+        };
+        let urls = candidate_urls(&stored, "http://127.0.0.1:9000/rest");
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].0, "http://127.0.0.1:9000/rest/PMC1/fullTextXML");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::cli::ExtractArgs;
+use crate::connectors::clinicaltrials;
 use crate::domain::doc::DocRecord;
 use crate::domain::evidence::{AnchorType, ClaimType, EvidenceLedgerRow};
 use crate::domain::figure::FigureIndexRow;
@@ -9,12 +10,17 @@ use crate::extract::figures_jats::extract_jats_figures;
 use crate::extract::figures_pdf::extract_pdf_figures;
 use crate::extract::numbers::parse_numbers;
 use crate::extract::pdf_text::extract_pdf_pages;
+use crate::extract::registry_results::results_to_claims;
+use crate::extract::trial_ids::find_nct_ids;
 use crate::extract::xml_text::extract_xml_sections;
+use crate::fs::atomic::write_atomic;
+use crate::fs::hash::sha256_bytes;
 use crate::pipeline::app::App;
 use crate::pipeline::io::write_jsonl;
 use chrono::Utc;
 use jsonschema::JSONSchema;
 use serde_json::Value;
+use std::collections::BTreeSet;
 use std::path::Path;
 use uuid::Uuid;
 
@@ -35,19 +41,25 @@ pub async fn run(app: &App, args: ExtractArgs) -> Result<()> {
 
     for doc in docs {
         if let Some(pdf_path) = &doc.local_pdf_path {
-            process_pdf(&doc, pdf_path, &mut ledger_rows)?;
+            for nct_id in process_pdf(&doc, pdf_path, &mut ledger_rows)? {
+                app.trials.link_doc(&doc.doc_id, &nct_id, "pdf")?;
+            }
             let out_dir = app.paths.local_doc_dir(&doc.doc_id).join("figures");
             let mut figs = extract_pdf_figures(&doc, Path::new(pdf_path), &out_dir)?;
             figure_rows.append(&mut figs);
         }
         if let Some(xml_path) = &doc.local_xml_path {
-            process_xml(&doc, xml_path, &mut ledger_rows)?;
+            for nct_id in process_xml(&doc, xml_path, &mut ledger_rows)? {
+                app.trials.link_doc(&doc.doc_id, &nct_id, "xml")?;
+            }
             let out_dir = app.paths.local_doc_dir(&doc.doc_id).join("figures");
             let mut figs = extract_jats_figures(&doc, Path::new(xml_path), &out_dir)?;
             figure_rows.append(&mut figs);
         }
     }
 
+    extract_registry_results(app, args.doc_ids.as_deref(), &mut ledger_rows).await?;
+
     enforce_schema_for_ledger(&mut ledger_rows, &evidence_schema)?;
     enforce_schema_for_figures(&figure_rows, &figure_schema)?;
 
@@ -63,9 +75,16 @@ pub async fn run(app: &App, args: ExtractArgs) -> Result<()> {
     Ok(())
 }
 
-fn process_pdf(doc: &DocRecord, pdf_path: &str, out: &mut Vec<EvidenceLedgerRow>) -> Result<()> {
+/// Appends claims from the PDF and returns the NCT ids it mentions.
+fn process_pdf(
+    doc: &DocRecord,
+    pdf_path: &str,
+    out: &mut Vec<EvidenceLedgerRow>,
+) -> Result<BTreeSet<String>> {
     let pages = extract_pdf_pages(Path::new(pdf_path))?;
+    let mut nct_ids = BTreeSet::new();
     for (page_no, text) in pages {
+        nct_ids.extend(find_nct_ids(&text));
         for candidate in generate_candidates(&text) {
             let verify = verify_candidate(&candidate, &text);
             let mut claim_text = candidate.sentence.clone();
@@ -88,6 +107,7 @@ fn process_pdf(doc: &DocRecord, pdf_path: &str, out: &mut Vec<EvidenceLedgerRow>
                 anchor_type: AnchorType::Pdf,
                 page_number: Some(page_no),
                 section_heading: None,
+                registry: None,
                 anchor_quote: if verify.anchor_quote.is_empty() {
                     candidate
                         .sentence
@@ -105,12 +125,20 @@ fn process_pdf(doc: &DocRecord, pdf_path: &str, out: &mut Vec<EvidenceLedgerRow>
             });
         }
     }
-    Ok(())
+    Ok(nct_ids)
 }
 
-fn process_xml(doc: &DocRecord, xml_path: &str, out: &mut Vec<EvidenceLedgerRow>) -> Result<()> {
+/// Appends claims from the JATS sections and returns the NCT ids they mention.
+fn process_xml(
+    doc: &DocRecord,
+    xml_path: &str,
+    out: &mut Vec<EvidenceLedgerRow>,
+) -> Result<BTreeSet<String>> {
     let sections = extract_xml_sections(Path::new(xml_path))?;
+    let mut nct_ids = BTreeSet::new();
     for section in sections {
+        nct_ids.extend(find_nct_ids(&section.heading));
+        nct_ids.extend(find_nct_ids(&section.body));
         for candidate in generate_candidates(&section.body) {
             let verify = verify_candidate(&candidate, &section.body);
             let mut claim_text = candidate.sentence.clone();
@@ -133,6 +161,7 @@ fn process_xml(doc: &DocRecord, xml_path: &str, out: &mut Vec<EvidenceLedgerRow>
                 anchor_type: AnchorType::Xml,
                 page_number: None,
                 section_heading: Some(section.heading.clone()),
+                registry: None,
                 anchor_quote: if verify.anchor_quote.is_empty() {
                     candidate
                         .sentence
@@ -150,6 +179,64 @@ fn process_xml(doc: &DocRecord, xml_path: &str, out: &mut Vec<EvidenceLedgerRow>
             });
         }
     }
+    Ok(nct_ids)
+}
+
+/// Pulls posted results for stored trials; a failed fetch skips that trial rather than the run.
+/// Each distinct payload is kept as `registry/<nct_id>-<sha256>.json` and never rewritten, so
+/// the path and hash in older ledgers keep matching what is on disk.
+async fn extract_registry_results(
+    app: &App,
+    doc_ids: Option<&str>,
+    out: &mut Vec<EvidenceLedgerRow>,
+) -> Result<()> {
+    let wanted = doc_ids.map(|csv| {
+        csv.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+    });
+
+    for trial in app.trials.list()? {
+        if !trial.has_results {
+            continue;
+        }
+        let Some(doc_id) = &trial.doc_id else {
+            continue;
+        };
+        if wanted.as_ref().is_some_and(|ids| !ids.contains(doc_id)) {
+            continue;
+        }
+        let Some(doc) = app.docs.get_doc(doc_id)? else {
+            continue;
+        };
+
+        let study = match clinicaltrials::get_study(&app.api, &trial.nct_id).await {
+            Ok(study) => study,
+            Err(err) => {
+                tracing::warn!(nct_id = %trial.nct_id, error = %err, "registry results fetch failed");
+                continue;
+            }
+        };
+        let bytes = serde_json::to_vec_pretty(&study)?;
+        let sha = sha256_bytes(&bytes);
+        let path = app
+            .paths
+            .local_doc_dir(doc_id)
+            .join("registry")
+            .join(format!("{}-{}.json", trial.nct_id, sha));
+        if !path.exists() {
+            write_atomic(&path, &bytes)?;
+        }
+
+        out.extend(results_to_claims(
+            &doc,
+            &trial.nct_id,
+            &study,
+            &path.to_string_lossy(),
+            &sha,
+        ));
+    }
     Ok(())
 }
 
@@ -0,0 +1,45 @@
+use crate::cli::{MeshArgs, MeshCommand};
+use crate::errors::{LitError, Result};
+use crate::pipeline::app::App;
+use crate::query::mesh::read_descriptors;
+use std::path::Path;
+
+pub async fn run(app: &App, args: MeshArgs) -> Result<()> {
+    match args.command {
+        MeshCommand::Load { file } => load(app, &file),
+        MeshCommand::Show { term } => show(app, &term),
+    }
+}
+
+fn load(app: &App, file: &Path) -> Result<()> {
+    let descriptors = read_descriptors(file)?;
+    let source = file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.display().to_string());
+    app.mesh.replace_all(&descriptors, &source)?;
+    let loaded = app.mesh.count()?;
+    tracing::info!(file = %file.display(), descriptors = loaded, "MeSH loaded");
+    println!("loaded {loaded} MeSH descriptors from {source}");
+    Ok(())
+}
+
+fn show(app: &App, term: &str) -> Result<()> {
+    let descriptor = app
+        .mesh
+        .find_by_term(term)?
+        .ok_or_else(|| LitError::NotFound(format!("MeSH term '{term}'")))?;
+    println!("{} {}", descriptor.ui, descriptor.name);
+    println!("tree: {}", descriptor.tree_numbers.join(", "));
+    println!("entry terms: {}", descriptor.entry_terms.join("; "));
+    let narrower = app.mesh.narrower(&descriptor)?;
+    println!(
+        "narrower: {}",
+        narrower
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    );
+    Ok(())
+}
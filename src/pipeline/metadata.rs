@@ -6,34 +6,59 @@ use crate::pipeline::io::read_json;
 use crate::types::SearchOutput;
 
 pub async fn run(app: &App, args: FetchArgs) -> Result<()> {
-    let mut payload: SearchOutput = read_json(&args.input)?;
+    store(app, args).await.map(|_| ())
+}
+
+/// Stores (and optionally enriches) the search results; returns the ids of the docs touched.
+pub async fn store(app: &App, args: FetchArgs) -> Result<Vec<String>> {
+    let payload: SearchOutput = read_json(&args.input)?;
+    let mut doc_ids: Vec<String> = Vec::new();
     let mut stored = 0usize;
     let mut enriched = 0usize;
+    let mut trials = 0usize;
+
+    for item in &payload.results {
+        let doc = app.docs.upsert_from_search(item)?;
+        stored += 1;
+        if !doc_ids.contains(&doc.doc_id) {
+            doc_ids.push(doc.doc_id.clone());
+        }
 
-    for item in &mut payload.results {
-        if args.enrich && item.oa_url.is_none() {
-            if let Some(doi) = &item.doi {
-                if !app.config.network.unpaywall_email.trim().is_empty() {
-                    if let Ok(oa) = unpaywall::resolve_oa_url(
-                        &app.api,
-                        doi,
-                        &app.config.network.unpaywall_email,
-                    )
-                    .await
-                    {
-                        if oa.is_some() {
-                            enriched += 1;
-                            item.oa_url = oa;
-                        }
-                    }
+        // Docs with a local or downloaded copy are already resolved; skip the lookup.
+        if args.enrich
+            && let Some(doi) = &item.doi
+            && !app.config.network.unpaywall_email.trim().is_empty()
+            && doc.local_pdf_path.is_none()
+            && doc.local_xml_path.is_none()
+        {
+            match unpaywall::lookup(&app.api, doi, &app.config.network.unpaywall_email).await {
+                Ok(oa) if !oa.locations.is_empty() => {
+                    app.docs
+                        .update_oa(&doc.doc_id, &oa.status, &oa.locations, oa.preferred())?;
+                    enriched += 1;
+                }
+                Ok(oa) if doc.oa_url.is_none() => {
+                    app.docs.update_oa(&doc.doc_id, &oa.status, &[], None)?;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::debug!(doi = %doi, error = %err, "unpaywall lookup failed");
                 }
             }
         }
 
-        let _ = app.docs.upsert_from_search(item)?;
-        stored += 1;
+        for nct_id in &item.trial_ids {
+            app.trials.link_doc(&doc.doc_id, nct_id, "pubmed_databank")?;
+        }
+
+        if let Some(trial) = &item.trial {
+            let mut trial = trial.clone();
+            trial.doc_id = Some(doc.doc_id.clone());
+            app.trials.upsert(&trial)?;
+            trials += 1;
+        }
     }
 
-    tracing::info!(stored, enriched, "metadata fetch complete");
-    Ok(())
+    tracing::info!(stored, enriched, trials, "metadata fetch complete");
+    Ok(doc_ids)
 }
@@ -1,10 +1,17 @@
 pub mod app;
+pub mod cache;
+pub mod docs;
 pub mod download_oa;
 pub mod extract;
 pub mod ingest_local;
 pub mod io;
+pub mod mesh;
 pub mod metadata;
 pub mod qa;
+pub mod reconcile;
 pub mod run;
 pub mod search;
+pub mod snowball;
+pub mod sources;
 pub mod synthesis;
+pub mod updates;
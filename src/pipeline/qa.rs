@@ -1,9 +1,17 @@
+use crate::brief::compose::BriefJson;
+use crate::brief::validate::retracted_citations;
 use crate::cli::QaArgs;
+use crate::domain::doc::UpdateStatus;
 use crate::domain::evidence::EvidenceLedgerRow;
 use crate::errors::{LitError, Result};
+use crate::extract::registry_results::verify_registry_quote;
+use crate::fs::hash::sha256_bytes;
 use crate::pipeline::app::App;
 use crate::pipeline::io::{read_json, read_jsonl};
 use crate::types::{QaReport, SearchOutput};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 
 pub async fn run(app: &App, args: QaArgs) -> Result<()> {
     let docs = app.docs.list_docs()?;
@@ -30,9 +38,14 @@ pub async fn run(app: &App, args: QaArgs) -> Result<()> {
 
     let ledger: Vec<EvidenceLedgerRow> = read_jsonl(&app.paths.evidence_ledger_path())?;
     let total_claims = ledger.len();
+    let mut snapshots = HashMap::new();
     let unanchored = ledger
         .iter()
-        .filter(|r| r.claim_text == "unknown" || r.errors.as_ref().is_some_and(|v| !v.is_empty()))
+        .filter(|r| {
+            r.claim_text == "unknown"
+                || r.errors.as_ref().is_some_and(|v| !v.is_empty())
+                || !registry_quote_holds(r, &mut snapshots)
+        })
         .count();
     let extraction_success_rate = if total_claims == 0 {
         0.0
@@ -40,16 +53,45 @@ pub async fn run(app: &App, args: QaArgs) -> Result<()> {
         (total_claims - unanchored) as f64 / total_claims as f64
     };
 
+    let retracted = docs
+        .iter()
+        .filter(|d| d.update_status == Some(UpdateStatus::Retracted))
+        .map(|d| d.doc_id.clone())
+        .collect::<HashSet<_>>();
+    let mut retracted_cited = Vec::new();
+    if !retracted.is_empty() && app.paths.briefs_root().exists() {
+        for entry in fs::read_dir(app.paths.briefs_root())? {
+            let path = entry?.path().join("brief.json");
+            if !path.exists() {
+                continue;
+            }
+            let brief: BriefJson = read_json(&path)?;
+            for doc_id in retracted_citations(&brief, &retracted) {
+                retracted_cited.push(format!("{}: {doc_id}", brief.slug));
+            }
+        }
+        retracted_cited.sort();
+    }
+
     let report = QaReport {
         unique_studies,
         duplicates_removed,
         oa_retrieval_rate,
         extraction_success_rate,
         unanchored_claim_count: unanchored,
+        retracted_citations: retracted_cited.clone(),
     };
 
     println!("{}", serde_json::to_string_pretty(&report)?);
 
+    // Citing a retracted paper fails regardless of strictness.
+    if !retracted_cited.is_empty() {
+        return Err(LitError::Pipeline(format!(
+            "qa gate failed: briefs cite retracted sources ({})",
+            retracted_cited.join(", ")
+        )));
+    }
+
     let strict = args.strict.unwrap_or(app.config.pipeline.strict_qa);
     if strict && unanchored > 0 {
         return Err(LitError::Pipeline(format!(
@@ -60,3 +102,68 @@ pub async fn run(app: &App, args: QaArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// For registry rows, re-reads the study snapshot the row cites and checks its hash and the
+/// quoted values; other rows pass. Snapshots are parsed once per path.
+fn registry_quote_holds(
+    row: &EvidenceLedgerRow,
+    snapshots: &mut HashMap<String, Option<Value>>,
+) -> bool {
+    let Some(anchor) = &row.registry else {
+        return true;
+    };
+    let study = snapshots.entry(row.local_path.clone()).or_insert_with(|| {
+        let bytes = fs::read(&row.local_path).ok()?;
+        if row.sha256.as_deref() != Some(sha256_bytes(&bytes).as_str()) {
+            return None;
+        }
+        serde_json::from_slice(&bytes).ok()
+    });
+    study
+        .as_ref()
+        .is_some_and(|study| verify_registry_quote(study, anchor, &row.anchor_quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::registry_quote_holds;
+    use crate::domain::evidence::EvidenceLedgerRow;
+    use crate::fs::hash::sha256_bytes;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn registry_quotes_are_checked_against_the_cited_snapshot() {
+        let study = json!({"resultsSection": {"adverseEventsModule": {
+            "eventGroups": [{"id": "EG000", "title": "TXA"}],
+            "seriousEvents": [{"term": "Seizure", "stats": [
+                {"groupId": "EG000", "numAffected": 2, "numAtRisk": 50}
+            ]}]
+        }}});
+        let bytes = serde_json::to_vec_pretty(&study).expect("json");
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("NCT01234567.json");
+        std::fs::write(&path, &bytes).expect("write");
+
+        let row = |quote: &str, sha: String| -> EvidenceLedgerRow {
+            serde_json::from_value(json!({
+                "claim_id": "c1", "doc_id": "doc_1", "source_type": "registry",
+                "doi": null, "pmid": null, "url": null,
+                "local_path": path.to_string_lossy(), "retrieved_at": "2024-01-01T00:00:00Z",
+                "sha256": sha, "anchor_type": "registry", "page_number": null,
+                "section_heading": null,
+                "registry": {"nct_id": "NCT01234567", "module": "adverseEventsModule",
+                             "index": 0, "measure_title": "Seizure"},
+                "anchor_quote": quote, "claim_text": "Serious adverse event Seizure",
+                "claim_type": "complication", "numbers": null, "errors": null
+            }))
+            .expect("row")
+        };
+        let sha = sha256_bytes(&bytes);
+        assert!(registry_quote_holds(&row("TXA: 2/50", sha.clone()), &mut HashMap::new()));
+        assert!(!registry_quote_holds(&row("TXA: 3/50", sha), &mut HashMap::new()));
+        // A snapshot that no longer hashes to the cited sha256 anchors nothing.
+        assert!(!registry_quote_holds(&row("TXA: 2/50", "0".repeat(64)), &mut HashMap::new()));
+    }
+}
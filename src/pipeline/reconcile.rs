@@ -0,0 +1,95 @@
+use crate::cli::ReconcileIdsArgs;
+use crate::connectors::idconv::{self, IdType};
+use crate::domain::doc::DocRecord;
+use crate::errors::Result;
+use crate::pipeline::app::App;
+use std::collections::HashMap;
+
+/// Fills missing DOI/PMID/PMCID via the PMC ID Converter and merges docs that turn out
+/// to be the same paper.
+pub async fn run(app: &App, args: ReconcileIdsArgs) -> Result<()> {
+    let wanted = args.doc_ids.as_ref().map(|csv| {
+        csv.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let docs = app
+        .docs
+        .list_docs()?
+        .into_iter()
+        .filter(|d| wanted.as_ref().is_none_or(|ids| ids.contains(&d.doc_id)))
+        .filter(needs_ids)
+        .collect::<Vec<_>>();
+
+    // One lookup per doc, keyed by the strongest id it already has.
+    let mut requests: HashMap<(IdType, String), Vec<String>> = HashMap::new();
+    for doc in &docs {
+        let key = if let Some(pmid) = &doc.pmid {
+            (IdType::Pmid, pmid.clone())
+        } else if let Some(pmcid) = &doc.pmcid {
+            (IdType::Pmcid, pmcid.clone())
+        } else if let Some(doi) = &doc.doi {
+            (IdType::Doi, doi.to_lowercase())
+        } else {
+            continue;
+        };
+        requests.entry(key).or_default().push(doc.doc_id.clone());
+    }
+
+    let email = app.config.network.ncbi_email();
+    let mut filled = 0usize;
+    let mut merged = 0usize;
+    for id_type in [IdType::Pmid, IdType::Pmcid, IdType::Doi] {
+        let ids = requests
+            .keys()
+            .filter(|(t, _)| *t == id_type)
+            .map(|(_, id)| id.clone())
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            continue;
+        }
+        let records = match idconv::convert(&app.api, &ids, id_type, email).await {
+            Ok(records) => records,
+            Err(err) => {
+                tracing::warn!(error = %err, "id conversion failed; leaving identifiers as-is");
+                continue;
+            }
+        };
+        for record in records {
+            let requested = match id_type {
+                IdType::Doi => record.requested.to_lowercase(),
+                _ => record.requested.clone(),
+            };
+            let Some(doc_ids) = requests.get(&(id_type, requested)) else {
+                continue;
+            };
+            for doc_id in doc_ids {
+                let outcome = app.docs.reconcile_ids(
+                    doc_id,
+                    record.doi.as_deref(),
+                    record.pmid.as_deref(),
+                    record.pmcid.as_deref(),
+                )?;
+                filled += usize::from(outcome.filled);
+                merged += outcome.merged.len();
+            }
+        }
+    }
+
+    tracing::info!(
+        candidates = docs.len(),
+        filled,
+        merged,
+        "identifier reconciliation complete"
+    );
+    Ok(())
+}
+
+fn needs_ids(doc: &DocRecord) -> bool {
+    let have = [&doc.doi, &doc.pmid, &doc.pmcid]
+        .iter()
+        .filter(|id| id.is_some())
+        .count();
+    have > 0 && have < 3
+}
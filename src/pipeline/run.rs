@@ -1,5 +1,6 @@
 use crate::cli::{
-    BriefArgs, BuildDigestArgs, DownloadOaArgs, ExtractArgs, FetchArgs, QaArgs, RunArgs, SearchArgs,
+    BriefArgs, BuildDigestArgs, CheckUpdatesArgs, DownloadOaArgs, ExtractArgs, FetchArgs, QaArgs,
+    ReconcileIdsArgs, RunArgs, SearchArgs,
 };
 use crate::errors::Result;
 use crate::pipeline::app::App;
@@ -9,14 +10,10 @@ pub async fn run(app: &App, args: RunArgs) -> Result<()> {
         query: args.query.clone(),
         since: args.since,
         limit: args.limit,
-        sources: vec![
-            "pubmed".to_string(),
-            "europepmc".to_string(),
-            "crossref".to_string(),
-            "openalex".to_string(),
-            "clinicaltrials".to_string(),
-        ],
+        sources: Vec::new(),
         out: Some(app.paths.search_output_path()),
+        explain: false,
+        expand_mesh: args.expand_mesh,
     };
     super::search::run(app, search_args).await?;
 
@@ -24,7 +21,30 @@ pub async fn run(app: &App, args: RunArgs) -> Result<()> {
         input: app.paths.search_output_path(),
         enrich: true,
     };
-    super::metadata::run(app, fetch_args).await?;
+    // Only this run's docs go to the ID Converter and Crossref, not the whole library.
+    let doc_ids = super::metadata::store(app, fetch_args).await?;
+    super::reconcile::run(
+        app,
+        ReconcileIdsArgs {
+            doc_ids: Some(doc_ids.join(",")),
+        },
+    )
+    .await?;
+    // Reconciliation may have merged some of them away; check the docs they now live in.
+    let mut checked: Vec<String> = Vec::new();
+    for doc_id in &doc_ids {
+        let kept = app.docs.resolve_doc_id(doc_id)?;
+        if !checked.contains(&kept) {
+            checked.push(kept);
+        }
+    }
+    super::updates::run(
+        app,
+        CheckUpdatesArgs {
+            doc_ids: Some(checked.join(",")),
+        },
+    )
+    .await?;
 
     super::download_oa::run(
         app,
@@ -1,37 +1,133 @@
 use crate::cli::SearchArgs;
-use crate::connectors::{clinicaltrials, crossref, epmc, openalex, pubmed};
-use crate::db::schema::title_hash;
-use crate::errors::Result;
+use crate::config::SourceFailurePolicy;
+use crate::connectors::registry::{Connector, SearchRequest, SourceBatch};
+use crate::db::schema::{normalize_doi, normalize_pmcid, normalize_pmid, title_hash};
+use crate::errors::{LitError, Result};
 use crate::pipeline::app::App;
-use crate::pipeline::io::write_json;
-use crate::types::{SearchOutput, SearchResult};
-use chrono::Utc;
-use std::collections::HashSet;
+use crate::pipeline::io::{read_json, write_json};
+use crate::query::ast::{QueryNode, parse_query};
+use crate::query::mesh::expand;
+use crate::query::render::render;
+use crate::types::{QueryExpansion, SearchOutput, SearchResult, SourceState, SourceStatus};
+use chrono::{NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
 
 pub async fn run(app: &App, args: SearchArgs) -> Result<()> {
+    let connectors = app.connectors.resolve(&args.sources)?;
+    let mut parsed = parse_query(&args.query)
+        .map_err(|e| LitError::Validation(format!("invalid --query: {e}")))?;
+    let expansion = if args.expand_mesh {
+        let mesh_source = app.mesh.source()?.ok_or_else(|| {
+            LitError::Validation(
+                "--expand-mesh needs a loaded vocabulary; run `lit mesh load --file <desc.xml>`".to_string(),
+            )
+        })?;
+        let (expanded, terms) = expand(&parsed, &app.mesh)?;
+        parsed = expanded;
+        Some(QueryExpansion {
+            mesh_source,
+            expanded_query: parsed.to_string(),
+            terms,
+        })
+    } else {
+        None
+    };
+
+    if args.explain {
+        println!("parsed: {parsed}");
+        for term in expansion.iter().flat_map(|e| &e.terms) {
+            println!(
+                "mesh: '{}' -> {} {} (+{} entry terms, +{} narrower)",
+                term.matched,
+                term.descriptor_ui,
+                term.descriptor,
+                term.entry_terms.len(),
+                term.narrower.len()
+            );
+        }
+        for connector in &connectors {
+            let translation = render(&parsed, connector.dialect());
+            println!("{:<16} {}", connector.name(), translation.query);
+            for note in translation.notes {
+                println!("{:<16} note: {note}", "");
+            }
+        }
+        return Ok(());
+    }
+
     let out_path = args.out.unwrap_or_else(|| app.paths.search_output_path());
-    let per_source_limit = (args.limit / args.sources.len().max(1)).max(10);
-    let mut all = Vec::new();
+    let today = resolution_day(app, &out_path);
+    let request = SearchRequest {
+        query: args.query.clone(),
+        parsed,
+        limit: (args.limit / connectors.len().max(1)).max(10),
+        since: match &args.since {
+            Some(window) => window.rebased(today),
+            None => app.config.pipeline.default_window_at(today)?,
+        },
+        today,
+    };
 
-    for source in &args.sources {
-        let mut rows = match source.as_str() {
-            "pubmed" => pubmed::search(&app.api, &args.query, per_source_limit, &args.since).await?,
-            "europepmc" => epmc::search(&app.api, &args.query, per_source_limit, &args.since).await?,
-            "crossref" => crossref::search(&app.api, &args.query, per_source_limit).await?,
-            "openalex" => openalex::search(&app.api, &args.query, per_source_limit).await?,
-            "clinicaltrials" => {
-                clinicaltrials::search(&app.api, &args.query, per_source_limit).await?
+    let mut all = Vec::new();
+    let mut source_status = Vec::new();
+    for (name, rendered_query, outcome, latency_ms) in search_all(app, &connectors, &request).await {
+        let status = match outcome {
+            Ok(mut batch) => {
+                let status = SourceStatus {
+                    source: name.to_string(),
+                    rendered_query: Some(rendered_query),
+                    status: if batch.partial_error.is_some() {
+                        SourceState::Partial
+                    } else {
+                        SourceState::Ok
+                    },
+                    count: batch.results.len(),
+                    total_available: batch.total_available,
+                    since_applied: batch.since_applied,
+                    error: batch.partial_error.take(),
+                    warnings: std::mem::take(&mut batch.warnings),
+                    latency_ms,
+                };
+                for (idx, result) in batch.results.iter_mut().enumerate() {
+                    result.fused_score = Some(rrf(idx + 1));
+                }
+                all.append(&mut batch.results);
+                status
             }
-            _ => Vec::new(),
+            Err(err) => SourceStatus {
+                source: name.to_string(),
+                rendered_query: Some(rendered_query),
+                status: SourceState::Failed,
+                count: 0,
+                total_available: None,
+                since_applied: false,
+                error: Some(err.to_string()),
+                warnings: vec![],
+                latency_ms,
+            },
         };
-        all.append(&mut rows);
+        if let Some(error) = &status.error {
+            tracing::warn!(source = name, state = ?status.status, %error, "source search degraded");
+        }
+        for warning in &status.warnings {
+            tracing::warn!(source = name, %warning, "source results incomplete");
+        }
+        source_status.push(status);
     }
 
-    let deduped = dedupe_results(all);
+    let ranked = rank_results(fuse_results(all), &request.parsed);
     let output = SearchOutput {
         query: args.query,
+        since: Some(request.since.clone()),
+        resolved_on: Some(request.today),
         generated_at: Utc::now(),
-        results: deduped.into_iter().take(args.limit).collect(),
+        results: ranked.into_iter().take(args.limit).collect(),
+        source_status,
+        expansion,
     };
     write_json(&out_path, &output)?;
 
@@ -41,26 +137,525 @@ pub async fn run(app: &App, args: SearchArgs) -> Result<()> {
         "search results written"
     );
 
+    check_failure_policy(app.config.search.fail_policy, &output.source_status)
+}
+
+/// Day relative windows resolve against, so that the requests rebuild URLs that can be
+/// answered: a replayed cassette's recording day, or offline, the day the previous
+/// `search_results.json` at `out_path` was resolved (the cache only holds those URLs).
+fn resolution_day(app: &App, out_path: &Path) -> NaiveDate {
+    if app.api.is_offline()
+        && let Ok(previous) = read_json::<SearchOutput>(out_path)
+    {
+        return previous
+            .resolved_on
+            .unwrap_or_else(|| previous.generated_at.date_naive());
+    }
+    app.api.today()
+}
+
+/// Runs every connector concurrently, each with the query rendered in its dialect; results
+/// come back in `connectors` order alongside the query each source received.
+async fn search_all(
+    app: &App,
+    connectors: &[Arc<dyn Connector>],
+    request: &SearchRequest,
+) -> Vec<(&'static str, String, Result<SourceBatch>, u64)> {
+    let rendered = connectors
+        .iter()
+        .map(|c| render(&request.parsed, c.dialect()).query)
+        .collect::<Vec<_>>();
+    let mut tasks = JoinSet::new();
+    for (idx, connector) in connectors.iter().enumerate() {
+        let connector = connector.clone();
+        let api = app.api.clone();
+        let request = SearchRequest {
+            query: rendered[idx].clone(),
+            ..request.clone()
+        };
+        tasks.spawn(async move {
+            let started = Instant::now();
+            let outcome = connector.search(&api, &request).await;
+            (idx, outcome, started.elapsed().as_millis() as u64)
+        });
+    }
+
+    let mut slots: Vec<Option<(Result<SourceBatch>, u64)>> = connectors.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((idx, outcome, latency_ms)) => slots[idx] = Some((outcome, latency_ms)),
+            Err(err) => tracing::error!(error = %err, "source search task panicked"),
+        }
+    }
+
+    connectors
+        .iter()
+        .zip(rendered)
+        .zip(slots)
+        .map(|((connector, query), slot)| {
+            let (outcome, latency_ms) = slot.unwrap_or_else(|| {
+                (Err(LitError::Pipeline("search task aborted".to_string())), 0)
+            });
+            (connector.name(), query, outcome, latency_ms)
+        })
+        .collect()
+}
+
+fn check_failure_policy(policy: SourceFailurePolicy, statuses: &[SourceStatus]) -> Result<()> {
+    let failed = statuses
+        .iter()
+        .filter(|s| s.status == SourceState::Failed)
+        .map(|s| s.source.as_str())
+        .collect::<Vec<_>>();
+    let fatal = match policy {
+        SourceFailurePolicy::Never => false,
+        SourceFailurePolicy::Any => !failed.is_empty(),
+        SourceFailurePolicy::All => !statuses.is_empty() && failed.len() == statuses.len(),
+    };
+    if fatal {
+        return Err(LitError::Pipeline(format!(
+            "search failed for source(s): {}",
+            failed.join(", ")
+        )));
+    }
     Ok(())
 }
 
-fn dedupe_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
-    let mut seen = HashSet::new();
-    let mut out = Vec::new();
+/// Fuses records that share a DOI, PMID, PMCID or title+year into one result per work.
+/// Each field keeps the value from the highest-priority connector that has one (see
+/// `source_rank`); keywords and trial ids are unioned.
+fn fuse_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut fused: Vec<Option<SearchResult>> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
 
     for item in results {
-        let key = if let Some(doi) = &item.doi {
-            format!("doi:{}", doi.to_lowercase())
-        } else if let Some(pmid) = &item.pmid {
-            format!("pmid:{}", pmid)
-        } else {
-            format!("title:{}", title_hash(&item.title))
+        let mut hits = identity_keys(&item)
+            .iter()
+            .filter_map(|k| by_key.get(k).copied())
+            .filter(|&idx| fused[idx].as_ref().is_some_and(|f| !conflicts(f, &item)))
+            .collect::<Vec<_>>();
+        hits.sort_unstable();
+        hits.dedup();
+
+        let idx = match hits.first() {
+            Some(&idx) => {
+                merge_result(fused[idx].as_mut().expect("live slot"), start_fusion(item));
+                // The new record can bridge two earlier works (DOI from one, PMID from another),
+                // but only works that do not conflict with what has been folded in so far.
+                for &other in &hits[1..] {
+                    let kept = fused[idx].as_ref().expect("live slot");
+                    if conflicts(kept, fused[other].as_ref().expect("live slot")) {
+                        continue;
+                    }
+                    // Keys of the absorbed record that the merged one drops (e.g. its title)
+                    // must still find the merged slot, not the emptied one.
+                    for slot in by_key.values_mut().filter(|slot| **slot == other) {
+                        *slot = idx;
+                    }
+                    let other = fused[other].take().expect("live slot");
+                    merge_result(fused[idx].as_mut().expect("live slot"), other);
+                }
+                idx
+            }
+            None => {
+                fused.push(Some(start_fusion(item)));
+                fused.len() - 1
+            }
         };
+        let keys = identity_keys(fused[idx].as_ref().expect("live slot"));
+        for key in keys {
+            by_key.insert(key, idx);
+        }
+    }
+
+    fused.into_iter().flatten().collect()
+}
+
+fn identity_keys(item: &SearchResult) -> Vec<String> {
+    let mut keys = Vec::new();
+    keys.extend(item.doi.as_deref().map(|d| format!("doi:{}", normalize_doi(d))));
+    keys.extend(
+        item.preprint
+            .as_ref()
+            .map(|p| format!("doi:{}", normalize_doi(&p.doi))),
+    );
+    keys.extend(item.pmid.as_deref().map(|p| format!("pmid:{}", normalize_pmid(p))));
+    keys.extend(item.pmcid.as_deref().map(|p| format!("pmcid:{}", normalize_pmcid(p))));
+    if !item.title.trim().is_empty() {
+        keys.push(format!(
+            "title:{}:{}",
+            title_hash(&item.title),
+            item.year.map(|y| y.to_string()).unwrap_or_default()
+        ));
+    }
+    keys
+}
+
+/// Same-titled records with different DOIs or PMIDs are distinct works (errata, replies).
+fn conflicts(a: &SearchResult, b: &SearchResult) -> bool {
+    let differs = |x: Option<String>, y: Option<String>| matches!((x, y), (Some(x), Some(y)) if x != y);
+    differs(a.doi.as_deref().map(normalize_doi), b.doi.as_deref().map(normalize_doi))
+        || differs(a.pmid.as_deref().map(normalize_pmid), b.pmid.as_deref().map(normalize_pmid))
+}
+
+/// Lower is preferred when two connectors disagree on a field.
+fn source_rank(source: &str) -> usize {
+    const ORDER: &[&str] = &[
+        "pubmed",
+        "europepmc",
+        "crossref",
+        "openalex",
+        "clinicaltrials",
+        "biorxiv",
+        "medrxiv",
+    ];
+    ORDER.iter().position(|s| *s == source).unwrap_or(ORDER.len())
+}
+
+/// Seeds `sources` and `field_sources` for a record straight from a connector.
+fn start_fusion(mut item: SearchResult) -> SearchResult {
+    if !item.sources.contains(&item.source) {
+        item.sources.insert(0, item.source.clone());
+    }
+    let present = present_fields(&item);
+    for field in present {
+        item.field_sources
+            .entry(field.to_string())
+            .or_insert_with(|| item.source.clone());
+    }
+    item
+}
+
+fn present_fields(item: &SearchResult) -> Vec<&'static str> {
+    [
+        ("doi", item.doi.is_some()),
+        ("pmid", item.pmid.is_some()),
+        ("pmcid", item.pmcid.is_some()),
+        ("title", !item.title.trim().is_empty()),
+        ("journal", item.journal.is_some()),
+        ("year", item.year.is_some()),
+        ("authors", !item.authors.is_empty()),
+        ("abstract_text", item.abstract_text.is_some()),
+        ("mesh_headings", !item.mesh_headings.is_empty()),
+        ("publication_types", !item.publication_types.is_empty()),
+        ("oa_url", item.oa_url.is_some()),
+        ("epmc_id", item.epmc_id.is_some()),
+        ("url", item.url.is_some()),
+        ("trial", item.trial.is_some()),
+        ("preprint", item.preprint.is_some()),
+    ]
+    .into_iter()
+    .filter(|(_, present)| *present)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+fn merge_result(target: &mut SearchResult, other: SearchResult) {
+    let SearchResult {
+        source,
+        doi,
+        pmid,
+        pmcid,
+        title,
+        journal,
+        year,
+        authors,
+        abstract_text,
+        mesh_headings,
+        publication_types,
+        keywords,
+        oa_url,
+        epmc_id,
+        url,
+        trial,
+        trial_ids,
+        preprint,
+        sources,
+        field_sources,
+        fused_score,
+    } = other;
+    let from = |field: &str| field_sources.get(field).cloned().unwrap_or_else(|| source.clone());
+    let fields = &mut target.field_sources;
+
+    pick(fields, "doi", &mut target.doi, doi, &from("doi"), Option::is_some);
+    pick(fields, "pmid", &mut target.pmid, pmid, &from("pmid"), Option::is_some);
+    pick(fields, "pmcid", &mut target.pmcid, pmcid, &from("pmcid"), Option::is_some);
+    pick(fields, "title", &mut target.title, title, &from("title"), |t| !t.trim().is_empty());
+    pick(fields, "journal", &mut target.journal, journal, &from("journal"), Option::is_some);
+    pick(fields, "year", &mut target.year, year, &from("year"), Option::is_some);
+    pick(fields, "authors", &mut target.authors, authors, &from("authors"), |v| !v.is_empty());
+    pick(fields, "abstract_text", &mut target.abstract_text, abstract_text, &from("abstract_text"), Option::is_some);
+    pick(fields, "mesh_headings", &mut target.mesh_headings, mesh_headings, &from("mesh_headings"), |v| !v.is_empty());
+    pick(fields, "publication_types", &mut target.publication_types, publication_types, &from("publication_types"), |v| !v.is_empty());
+    pick(fields, "oa_url", &mut target.oa_url, oa_url, &from("oa_url"), Option::is_some);
+    pick(fields, "epmc_id", &mut target.epmc_id, epmc_id, &from("epmc_id"), Option::is_some);
+    pick(fields, "url", &mut target.url, url, &from("url"), Option::is_some);
+    pick(fields, "trial", &mut target.trial, trial, &from("trial"), Option::is_some);
+    pick(fields, "preprint", &mut target.preprint, preprint, &from("preprint"), Option::is_some);
 
-        if seen.insert(key) {
-            out.push(item);
+    for keyword in keywords {
+        if !target.keywords.iter().any(|k| k.eq_ignore_ascii_case(&keyword)) {
+            target.keywords.push(keyword);
+        }
+    }
+    for id in trial_ids {
+        if !target.trial_ids.contains(&id) {
+            target.trial_ids.push(id);
+        }
+    }
+    // Each source's reciprocal-rank contribution is summed across the fused records.
+    target.fused_score = match (target.fused_score, fused_score) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    };
+    for name in std::iter::once(source).chain(sources) {
+        if !target.sources.contains(&name) {
+            target.sources.push(name);
+        }
+    }
+}
+
+/// Conventional RRF damping constant; keeps one source's top hit from dominating.
+const RRF_K: f64 = 60.0;
+
+fn rrf(rank: usize) -> f64 {
+    1.0 / (RRF_K + rank as f64)
+}
+
+/// Adds lexical relevance to the per-source RRF scores as one more ranked list and sorts
+/// best-first, so the `--limit` cut is by relevance rather than by source order.
+fn rank_results(mut results: Vec<SearchResult>, query: &QueryNode) -> Vec<SearchResult> {
+    let lexical = results
+        .iter()
+        .map(|r| lexical_relevance(r, query))
+        .collect::<Vec<_>>();
+    for (idx, result) in results.iter_mut().enumerate() {
+        // Competition ranking: equally relevant records share a rank.
+        let rank = 1 + lexical.iter().filter(|other| **other > lexical[idx]).count();
+        result.fused_score = Some(result.fused_score.unwrap_or_default() + rrf(rank));
+    }
+    results.sort_by(|a, b| {
+        b.fused_score
+            .unwrap_or_default()
+            .total_cmp(&a.fused_score.unwrap_or_default())
+    });
+    results
+}
+
+/// Share of the query's top-level AND clauses the record satisfies: a title match counts 1,
+/// an abstract or MeSH match 0.5. Exclusions are ignored.
+fn lexical_relevance(result: &SearchResult, query: &QueryNode) -> f64 {
+    let clauses = match query {
+        QueryNode::And(children) => children
+            .iter()
+            .filter(|c| !matches!(c, QueryNode::Not(_)))
+            .collect::<Vec<_>>(),
+        QueryNode::Not(_) => vec![],
+        other => vec![other],
+    };
+    if clauses.is_empty() {
+        return 0.0;
+    }
+    let mesh = result
+        .mesh_headings
+        .iter()
+        .map(|m| m.descriptor.clone())
+        .collect::<Vec<_>>();
+    let abstract_text = result.abstract_text.as_deref().unwrap_or_default();
+    let score = clauses
+        .iter()
+        .map(|clause| {
+            if clause.matches(&result.title, "", &[]) {
+                1.0
+            } else if clause.matches("", abstract_text, &mesh) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+        .sum::<f64>();
+    score / clauses.len() as f64
+}
+
+/// Replaces `mine` with `theirs` when `mine` is empty or `their_source` outranks the
+/// connector that supplied `mine`.
+fn pick<T>(
+    fields: &mut BTreeMap<String, String>,
+    name: &str,
+    mine: &mut T,
+    theirs: T,
+    their_source: &str,
+    present: impl Fn(&T) -> bool,
+) {
+    if !present(&theirs) {
+        return;
+    }
+    let replace = !present(mine)
+        || fields
+            .get(name)
+            .is_none_or(|current| source_rank(their_source) < source_rank(current));
+    if replace {
+        *mine = theirs;
+        fields.insert(name.to_string(), their_source.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_failure_policy, fuse_results, rank_results, rrf};
+    use crate::query::ast::parse_query;
+    use crate::config::SourceFailurePolicy;
+    use crate::types::{SearchResult, SourceState, SourceStatus};
+
+    fn result(source: &str, doi: Option<&str>, pmid: Option<&str>) -> SearchResult {
+        SearchResult {
+            source: source.to_string(),
+            doi: doi.map(ToString::to_string),
+            pmid: pmid.map(ToString::to_string),
+            title: "Tranexamic acid in chronic subdural hematoma".to_string(),
+            year: Some(2024),
+            ..Default::default()
         }
     }
 
-    out
+    fn status(source: &str, state: SourceState) -> SourceStatus {
+        SourceStatus {
+            source: source.to_string(),
+            rendered_query: None,
+            status: state,
+            count: 0,
+            total_available: None,
+            since_applied: false,
+            error: None,
+            warnings: vec![],
+            latency_ms: 0,
+        }
+    }
+
+    #[test]
+    fn failure_policy_decides_fatality() {
+        let mixed = vec![
+            status("pubmed", SourceState::Ok),
+            status("crossref", SourceState::Failed),
+        ];
+        let all_failed = vec![
+            status("pubmed", SourceState::Failed),
+            status("crossref", SourceState::Failed),
+        ];
+
+        assert!(check_failure_policy(SourceFailurePolicy::All, &mixed).is_ok());
+        assert!(check_failure_policy(SourceFailurePolicy::All, &all_failed).is_err());
+        assert!(check_failure_policy(SourceFailurePolicy::Any, &mixed).is_err());
+        assert!(check_failure_policy(SourceFailurePolicy::Never, &all_failed).is_ok());
+    }
+
+    #[test]
+    fn duplicates_are_fused_field_by_field() {
+        let crossref = SearchResult {
+            journal: Some("J Neurosurg".into()),
+            oa_url: Some("https://example.org/a.pdf".into()),
+            ..result("crossref", Some("10.1000/TXA"), None)
+        };
+        let pubmed = SearchResult {
+            journal: Some("Journal of neurosurgery".into()),
+            abstract_text: Some("Randomized trial.".into()),
+            ..result("pubmed", Some("10.1000/txa"), Some("123"))
+        };
+        // Bridged to the same work by PMID only; the same-titled openalex record has its own DOI.
+        let epmc = SearchResult {
+            pmcid: Some("PMC9".into()),
+            abstract_text: Some("EPMC abstract.".into()),
+            title: "Different title casing".into(),
+            ..result("europepmc", None, Some("123"))
+        };
+        let other = result("openalex", Some("10.1000/other"), None);
+
+        let fused = fuse_results(vec![crossref, pubmed, epmc, other]);
+        assert_eq!(fused.len(), 2);
+        let work = &fused[0];
+        assert_eq!(work.sources, vec!["crossref", "pubmed", "europepmc"]);
+        assert_eq!(work.pmid.as_deref(), Some("123"));
+        assert_eq!(work.pmcid.as_deref(), Some("PMC9"));
+        assert_eq!(work.journal.as_deref(), Some("Journal of neurosurgery"));
+        assert_eq!(work.abstract_text.as_deref(), Some("Randomized trial."));
+        assert_eq!(work.oa_url.as_deref(), Some("https://example.org/a.pdf"));
+        assert_eq!(work.field_sources["doi"], "pubmed");
+        assert_eq!(work.field_sources["pmcid"], "europepmc");
+        assert_eq!(work.field_sources["oa_url"], "crossref");
+        assert_eq!(fused[1].sources, vec!["openalex"]);
+    }
+
+    #[test]
+    fn a_bridging_record_does_not_merge_conflicting_works() {
+        let a = result("crossref", Some("10.1000/a"), Some("1"));
+        let b = SearchResult {
+            pmcid: Some("PMC7".into()),
+            ..result("pubmed", None, Some("2"))
+        };
+        // Shares a DOI with `a` and a PMCID with `b`, but `a` and `b` have different PMIDs.
+        let bridge = SearchResult {
+            pmcid: Some("PMC7".into()),
+            ..result("europepmc", Some("10.1000/a"), None)
+        };
+
+        let fused = fuse_results(vec![a, b, bridge]);
+        assert_eq!(fused.len(), 2);
+        assert_eq!(fused[0].sources, vec!["crossref", "europepmc"]);
+        assert_eq!(fused[0].pmid.as_deref(), Some("1"));
+        assert_eq!(fused[1].sources, vec!["pubmed"]);
+        assert_eq!(fused[1].pmid.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn a_later_record_finds_the_work_by_an_absorbed_title() {
+        let doi_only = result("pubmed", Some("10.1000/a"), None);
+        let pmid_only = SearchResult {
+            title: "Tranexamic acid for chronic subdural haematoma".into(),
+            ..result("crossref", None, Some("1"))
+        };
+        let bridge = result("europepmc", Some("10.1000/a"), Some("1"));
+        // Matches nothing but the absorbed crossref record's title.
+        let title_only = SearchResult {
+            title: "Tranexamic acid for chronic subdural haematoma".into(),
+            ..result("openalex", None, None)
+        };
+
+        let fused = fuse_results(vec![doi_only, pmid_only, bridge, title_only]);
+        assert_eq!(fused.len(), 1);
+        assert_eq!(
+            fused[0].sources,
+            vec!["pubmed", "europepmc", "crossref", "openalex"]
+        );
+        assert_eq!(fused[0].title, "Tranexamic acid in chronic subdural hematoma");
+    }
+
+    #[test]
+    fn ranking_mixes_source_rank_and_lexical_relevance() {
+        let ranked_by = |source: &str, titles: &[&str]| {
+            titles
+                .iter()
+                .enumerate()
+                .map(|(idx, title)| SearchResult {
+                    title: title.to_string(),
+                    fused_score: Some(rrf(idx + 1)),
+                    ..result(source, Some(&format!("10.1/{title}")), None)
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut all = ranked_by("pubmed", &["Spinal fusion outcomes", "Tranexamic acid for subdural hematoma"]);
+        all.extend(ranked_by("clinicaltrials", &["Tranexamic acid in chronic subdural hematoma"]));
+
+        let query = parse_query("tranexamic subdural").expect("parse");
+        let ranked = rank_results(fuse_results(all), &query);
+        let titles = ranked.iter().map(|r| r.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Tranexamic acid in chronic subdural hematoma",
+                "Tranexamic acid for subdural hematoma",
+                "Spinal fusion outcomes",
+            ]
+        );
+        assert!(ranked.iter().all(|r| r.fused_score.is_some()));
+    }
 }
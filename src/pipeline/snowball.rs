@@ -0,0 +1,128 @@
+use crate::cli::{SnowballArgs, SnowballDirection};
+use crate::connectors::openalex::{self, CitationWork};
+use crate::errors::{LitError, Result};
+use crate::pipeline::app::App;
+use std::collections::HashSet;
+
+/// Backward/forward citation chasing from seed docs via OpenAlex. Every work reached is
+/// upserted as a doc and tagged "snowball from <seed>", until `--max-works` works are stored.
+pub async fn run(app: &App, args: SnowballArgs) -> Result<()> {
+    let seeds = args
+        .doc_ids
+        .split(',')
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    if seeds.is_empty() {
+        return Err(LitError::Validation(
+            "--doc-ids needs at least one doc id".to_string(),
+        ));
+    }
+
+    let mut budget = args.max_works;
+    for seed_id in seeds {
+        if budget == 0 {
+            println!("--max-works {} reached; remaining seeds skipped", args.max_works);
+            break;
+        }
+        let seed = app
+            .docs
+            .get_doc(&seed_id)?
+            .ok_or_else(|| LitError::NotFound(format!("doc {seed_id}")))?;
+        let root = match openalex::work_by_ids(&app.api, seed.doi.as_deref(), seed.pmid.as_deref())
+            .await
+        {
+            Ok(Some(work)) => work,
+            Ok(None) => {
+                println!("{seed_id}: no OpenAlex work found by DOI or PMID");
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!(doc_id = %seed_id, error = %err, "seed not found in OpenAlex");
+                println!("{seed_id}: not found in OpenAlex");
+                continue;
+            }
+        };
+
+        let provenance = format!("snowball from {seed_id}");
+        let before = app.docs.count_docs()?;
+        let mut seen = HashSet::from([root.id.clone()]);
+        let mut frontier = vec![root];
+        let mut linked = 0usize;
+
+        for level in 1..=args.depth {
+            let mut next = Vec::new();
+            for work in &frontier {
+                if budget == 0 {
+                    break;
+                }
+                for found in neighbours(app, work, &seen, &args, budget).await {
+                    if budget == 0 {
+                        break;
+                    }
+                    if !seen.insert(found.id.clone()) {
+                        continue;
+                    }
+                    let doc = app.docs.upsert_from_search(&found.result)?;
+                    budget -= 1;
+                    if doc.doc_id != seed_id {
+                        app.docs.add_provenance(&doc.doc_id, &provenance)?;
+                        linked += 1;
+                    }
+                    next.push(found);
+                }
+            }
+            tracing::debug!(doc_id = %seed_id, level, works = next.len(), "snowball level done");
+            if budget == 0 {
+                tracing::warn!(
+                    doc_id = %seed_id,
+                    level,
+                    max_works = args.max_works,
+                    "snowball stopped at --max-works"
+                );
+                break;
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let new_docs = app.docs.count_docs()?.saturating_sub(before);
+        tracing::info!(doc_id = %seed_id, linked, new_docs, "snowball seed complete");
+        println!("{seed_id}: {linked} linked works, {new_docs} new unique studies");
+    }
+    Ok(())
+}
+
+/// References and/or citing works of `work`, at most `limit` of each; lookup failures are
+/// logged and skipped so one bad work does not end the snowball.
+async fn neighbours(
+    app: &App,
+    work: &CitationWork,
+    seen: &HashSet<String>,
+    args: &SnowballArgs,
+    limit: usize,
+) -> Vec<CitationWork> {
+    let mut out = Vec::new();
+    if args.direction != SnowballDirection::Forward {
+        let refs = work
+            .referenced_works
+            .iter()
+            .filter(|id| !seen.contains(*id))
+            .take(limit)
+            .cloned()
+            .collect::<Vec<_>>();
+        match openalex::works_by_openalex_ids(&app.api, &refs).await {
+            Ok(works) => out.extend(works),
+            Err(err) => tracing::warn!(work = %work.id, error = %err, "reference lookup failed"),
+        }
+    }
+    if args.direction != SnowballDirection::Back {
+        match openalex::citing_works(&app.api, &work.id, args.max_citing.min(limit)).await {
+            Ok(works) => out.extend(works),
+            Err(err) => tracing::warn!(work = %work.id, error = %err, "citation lookup failed"),
+        }
+    }
+    out
+}
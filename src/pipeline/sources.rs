@@ -0,0 +1,33 @@
+use crate::cli::{SourcesArgs, SourcesCommand};
+use crate::errors::Result;
+use crate::pipeline::app::App;
+
+pub async fn run(app: &App, args: SourcesArgs) -> Result<()> {
+    match args.command {
+        SourcesCommand::List => list(app),
+    }
+    Ok(())
+}
+
+fn list(app: &App) {
+    println!(
+        "{:<16} {:<8} {:<12} {:<11} {:<14} DESCRIPTION",
+        "NAME", "ENABLED", "DATE_FILTER", "PAGINATION", "OA_RESOLUTION"
+    );
+    for connector in app.connectors.all() {
+        let caps = connector.capabilities();
+        println!(
+            "{:<16} {:<8} {:<12} {:<11} {:<14} {}",
+            connector.name(),
+            yes_no(app.connectors.is_enabled(connector.name())),
+            yes_no(caps.date_filter),
+            yes_no(caps.pagination),
+            yes_no(caps.oa_resolution),
+            connector.description()
+        );
+    }
+}
+
+fn yes_no(v: bool) -> &'static str {
+    if v { "yes" } else { "no" }
+}
@@ -1,21 +1,26 @@
-use crate::brief::compose::{BriefJson, compose_brief};
+use crate::brief::compose::{BriefJson, BriefTrial, compose_brief};
 use crate::brief::fig_integration::integrate_figures_and_sources;
 use crate::brief::rank::score_claim;
 use crate::brief::render_md::render_markdown;
 use crate::brief::render_pdf::render_pdf;
 use crate::brief::validate::{validate_brief, validate_brief_figures};
 use crate::cli::{BriefArgs, BuildDigestArgs};
-use crate::domain::evidence::EvidenceLedgerRow;
+use crate::db::trials_repo::TrialsRepo;
+use crate::domain::doc::UpdateStatus;
+use crate::domain::evidence::{EvidenceLedgerRow, RegistryAnchor};
 use crate::domain::figure::FigureIndexRow;
+use crate::domain::trial::{DocTrialLink, TrialRecord};
 use crate::errors::Result;
 use crate::pipeline::app::App;
 use crate::pipeline::io::{read_json, read_jsonl, write_json};
-use std::collections::HashMap;
+use crate::query::ast::{QueryNode, parse_query};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
+const MAX_BRIEF_TRIALS: usize = 20;
+
 pub async fn run_digest(app: &App, args: BuildDigestArgs) -> Result<()> {
-    let ledger: Vec<EvidenceLedgerRow> = read_jsonl(&app.paths.evidence_ledger_path())?;
-    let figures: Vec<FigureIndexRow> = read_jsonl(&app.paths.figures_index_path())?;
+    let (ledger, figures) = read_extraction(app)?;
     let docs = app.docs.list_docs()?;
     let doc_map = docs
         .iter()
@@ -38,14 +43,42 @@ pub async fn run_digest(app: &App, args: BuildDigestArgs) -> Result<()> {
     let brief_dir = app.paths.brief_dir(&slug);
     fs::create_dir_all(&brief_dir)?;
 
-    let brief = compose_brief(
+    let retracted = docs
+        .iter()
+        .filter(|d| d.update_status == Some(UpdateStatus::Retracted))
+        .map(|d| d.doc_id.clone())
+        .collect::<HashSet<_>>();
+    if !retracted.is_empty() {
+        tracing::info!(retracted = retracted.len(), "excluding claims from retracted docs");
+    }
+    let mut brief = compose_brief(
         slug.clone(),
-        args.query,
+        args.query.clone(),
         ranked,
         figures,
+        &retracted,
         8,
         app.config.pipeline.max_key_figures,
     );
+    let cited_docs = brief
+        .citations
+        .iter()
+        .map(|c| c.doc_id.clone())
+        .collect::<HashSet<_>>();
+    brief.trials = brief_trials(&app.trials, &cited_docs, &args.query, MAX_BRIEF_TRIALS)?
+        .into_iter()
+        .map(BriefTrial::from)
+        .collect();
+    for trial in &mut brief.trials {
+        trial.publications = app
+            .trials
+            .links_for_trial(&trial.nct_id)?
+            .into_iter()
+            .filter(DocTrialLink::reports)
+            .map(|l| l.doc_id)
+            .collect();
+        trial.publications.dedup();
+    }
     write_json(&brief_dir.join("brief.json"), &brief)?;
 
     let stubs = docs
@@ -64,13 +97,50 @@ pub async fn run_digest(app: &App, args: BuildDigestArgs) -> Result<()> {
     Ok(())
 }
 
+/// Trials linked to a cited doc (or registered as one) first, then trials whose title,
+/// conditions or interventions match `query`; unrelated trials in the DB are left out.
+pub fn brief_trials(
+    repo: &TrialsRepo,
+    cited_docs: &HashSet<String>,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<TrialRecord>> {
+    let mut linked = HashSet::new();
+    for doc_id in cited_docs {
+        linked.extend(repo.links_for_doc(doc_id)?.into_iter().map(|l| l.nct_id));
+    }
+    let (mut selected, rest): (Vec<_>, Vec<_>) = repo.list()?.into_iter().partition(|t| {
+        linked.contains(&t.nct_id) || t.doc_id.as_ref().is_some_and(|d| cited_docs.contains(d))
+    });
+    if let Ok(parsed) = parse_query(query) {
+        selected.extend(rest.into_iter().filter(|t| trial_matches(&parsed, t)));
+    }
+    selected.truncate(limit);
+    Ok(selected)
+}
+
+fn trial_matches(query: &QueryNode, trial: &TrialRecord) -> bool {
+    let title = format!(
+        "{} {}",
+        trial.title,
+        trial.official_title.as_deref().unwrap_or_default()
+    );
+    let details = trial
+        .conditions
+        .iter()
+        .chain(trial.interventions.iter().map(|i| &i.name))
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ");
+    query.matches(&title, &details, &[])
+}
+
 pub async fn run_brief(app: &App, args: BriefArgs) -> Result<()> {
     let brief_dir = app.paths.brief_dir(&args.brief_slug);
     let brief_json_path = brief_dir.join("brief.json");
     let mut brief: BriefJson = read_json(&brief_json_path)?;
 
-    let figures: Vec<FigureIndexRow> = read_jsonl(&app.paths.figures_index_path())?;
-    let ledger: Vec<EvidenceLedgerRow> = read_jsonl(&app.paths.evidence_ledger_path())?;
+    let (ledger, figures) = read_extraction(app)?;
     let docs = app.docs.list_docs()?;
 
     integrate_figures_and_sources(
@@ -98,6 +168,24 @@ pub async fn run_brief(app: &App, args: BriefArgs) -> Result<()> {
     Ok(())
 }
 
+/// Ledger and figure-index rows, with doc ids of merged-away docs mapped to the doc that
+/// absorbed them.
+fn read_extraction(app: &App) -> Result<(Vec<EvidenceLedgerRow>, Vec<FigureIndexRow>)> {
+    let aliases = app.docs.aliases()?;
+    let mut ledger: Vec<EvidenceLedgerRow> = read_jsonl(&app.paths.evidence_ledger_path())?;
+    let mut figures: Vec<FigureIndexRow> = read_jsonl(&app.paths.figures_index_path())?;
+    for doc_id in ledger
+        .iter_mut()
+        .map(|r| &mut r.doc_id)
+        .chain(figures.iter_mut().map(|f| &mut f.doc_id))
+    {
+        if let Some(kept) = aliases.get(doc_id.as_str()) {
+            *doc_id = kept.clone();
+        }
+    }
+    Ok((ledger, figures))
+}
+
 fn build_digest_markdown(query: &str, ranked: &[EvidenceLedgerRow]) -> String {
     let mut out = String::new();
     out.push_str(&format!("# Digest for query: {}\n\n", query));
@@ -111,6 +199,11 @@ fn build_digest_markdown(query: &str, ranked: &[EvidenceLedgerRow]) -> String {
                 .section_heading
                 .clone()
                 .unwrap_or_else(|| "Unknown section".to_string()),
+            crate::domain::evidence::AnchorType::Registry => claim
+                .registry
+                .as_ref()
+                .map(RegistryAnchor::label)
+                .unwrap_or_else(|| "Unknown registry record".to_string()),
         };
         out.push_str(&format!(
             "{}. {}\n   - claim_id: `{}`\n   - doc_id: `{}` doi={:?} pmid={:?}\n   - anchor: {}\n   - quote: \"{}\"\n",
@@ -0,0 +1,108 @@
+use crate::cli::CheckUpdatesArgs;
+use crate::connectors::{crossref, pubmed};
+use crate::domain::doc::UpdateStatus;
+use crate::errors::Result;
+use crate::pipeline::app::App;
+
+/// Flags retracted/corrected docs from Crossref `update-to` notices, PubMed
+/// CommentsCorrections relations and PubMed publication types. The most severe notice wins;
+/// docs with none found are cleared. When the Crossref or PubMed lookup fails, a stored
+/// status is only replaced by one at least as severe.
+pub async fn run(app: &App, args: CheckUpdatesArgs) -> Result<()> {
+    let wanted = args.doc_ids.as_ref().map(|csv| {
+        csv.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let docs = app
+        .docs
+        .list_docs()?
+        .into_iter()
+        .filter(|d| wanted.as_ref().is_none_or(|ids| ids.contains(&d.doc_id)))
+        .collect::<Vec<_>>();
+
+    let mut retracted = 0usize;
+    let mut flagged = 0usize;
+    for doc in &docs {
+        let mut found = UpdateStatus::from_publication_types(&doc.publication_types)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut complete = true;
+        if let Some(pmid) = &doc.pmid {
+            let api_key = &app.config.network.pubmed_api_key;
+            match pubmed::updates_for(&app.api, pmid, api_key).await {
+                Ok(updates) => found.extend(updates),
+                Err(err) => {
+                    tracing::warn!(doc_id = %doc.doc_id, pmid = %pmid, error = %err, "pubmed update lookup failed");
+                    complete = false;
+                }
+            }
+        }
+        if let Some(doi) = &doc.doi {
+            match crossref::updates_for(&app.api, doi).await {
+                Ok(updates) => found.extend(updates),
+                Err(err) => {
+                    tracing::warn!(doc_id = %doc.doc_id, doi = %doi, error = %err, "crossref update lookup failed");
+                    complete = false;
+                }
+            }
+        }
+
+        let worst = found.into_iter().min_by_key(|(status, _)| *status);
+        let status = worst.as_ref().map(|(s, _)| *s);
+        if !may_replace(doc.update_status, status, complete) {
+            // Keep whatever was recorded before rather than downgrading it on a failed lookup.
+            continue;
+        }
+        if status != doc.update_status {
+            app.docs.set_update_status(
+                &doc.doc_id,
+                status,
+                worst.as_ref().map(|(_, notice)| notice.as_str()),
+            )?;
+        }
+        if let Some((status, notice)) = &worst {
+            flagged += 1;
+            if *status == UpdateStatus::Retracted {
+                retracted += 1;
+            }
+            tracing::info!(doc_id = %doc.doc_id, status = status.as_str(), notice = %notice, "post-publication update found");
+        }
+    }
+
+    tracing::info!(
+        checked = docs.len(),
+        flagged,
+        retracted,
+        "update check complete"
+    );
+    Ok(())
+}
+
+/// Whether `found` may overwrite the `stored` status. After an incomplete check only a
+/// status at least as severe may, so a failed lookup never clears or downgrades a flag.
+fn may_replace(stored: Option<UpdateStatus>, found: Option<UpdateStatus>, complete: bool) -> bool {
+    match (stored, found) {
+        _ if complete => true,
+        (None, _) => true,
+        (Some(old), Some(new)) => new <= old,
+        (Some(_), None) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::may_replace;
+    use crate::domain::doc::UpdateStatus::{Corrected, Retracted};
+
+    #[test]
+    fn failed_lookups_never_downgrade_a_stored_status() {
+        assert!(!may_replace(Some(Retracted), Some(Corrected), false));
+        assert!(!may_replace(Some(Corrected), None, false));
+        assert!(may_replace(Some(Corrected), Some(Retracted), false));
+        assert!(may_replace(None, Some(Corrected), false));
+        assert!(may_replace(Some(Retracted), Some(Corrected), true));
+        assert!(may_replace(Some(Retracted), None, true));
+    }
+}
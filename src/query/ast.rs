@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Field a term is restricted to via a `title:`/`abstract:`/`mesh:` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Any,
+    Title,
+    Abstract,
+    Mesh,
+}
+
+impl Field {
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.to_lowercase().as_str() {
+            "title" | "ti" => Some(Field::Title),
+            "abstract" | "ab" => Some(Field::Abstract),
+            "mesh" | "mh" => Some(Field::Mesh),
+            _ => None,
+        }
+    }
+
+    fn tag(self) -> Option<&'static str> {
+        match self {
+            Field::Any => None,
+            Field::Title => Some("title"),
+            Field::Abstract => Some("abstract"),
+            Field::Mesh => Some("mesh"),
+        }
+    }
+}
+
+/// Parsed `--query`: terms, quoted phrases, `AND`/`OR`/`NOT` (or a leading `-`), parentheses
+/// and field tags. Adjacent terms are implicitly ANDed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryNode {
+    Term { text: String, field: Field },
+    Phrase { text: String, field: Field },
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    /// Evaluates the query against a record's text, for sources that cannot search themselves.
+    /// Terms match case-insensitively as substrings; a trailing `*` is treated as a prefix.
+    pub fn matches(&self, title: &str, abstract_text: &str, mesh: &[String]) -> bool {
+        match self {
+            QueryNode::Term { text, field } | QueryNode::Phrase { text, field } => {
+                let needle = text.trim_end_matches('*').to_lowercase();
+                let contains = |hay: &str| hay.to_lowercase().contains(&needle);
+                match field {
+                    Field::Any => {
+                        contains(title)
+                            || contains(abstract_text)
+                            || mesh.iter().any(|m| contains(m))
+                    }
+                    Field::Title => contains(title),
+                    Field::Abstract => contains(abstract_text),
+                    Field::Mesh => mesh.iter().any(|m| contains(m)),
+                }
+            }
+            QueryNode::And(children) => children
+                .iter()
+                .all(|c| c.matches(title, abstract_text, mesh)),
+            QueryNode::Or(children) => children
+                .iter()
+                .any(|c| c.matches(title, abstract_text, mesh)),
+            QueryNode::Not(inner) => !inner.matches(title, abstract_text, mesh),
+        }
+    }
+
+    /// Positive terms and phrases in query order, skipping anything under `NOT`.
+    pub fn positive_leaves(&self) -> Vec<&QueryNode> {
+        match self {
+            QueryNode::Term { .. } | QueryNode::Phrase { .. } => vec![self],
+            QueryNode::And(children) | QueryNode::Or(children) => {
+                children.iter().flat_map(|c| c.positive_leaves()).collect()
+            }
+            QueryNode::Not(_) => vec![],
+        }
+    }
+}
+
+impl fmt::Display for QueryNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn child(node: &QueryNode) -> String {
+            match node {
+                QueryNode::And(c) | QueryNode::Or(c) if c.len() > 1 => format!("({node})"),
+                _ => node.to_string(),
+            }
+        }
+        match self {
+            QueryNode::Term { text, field } | QueryNode::Phrase { text, field } => {
+                if let Some(tag) = field.tag() {
+                    write!(f, "{tag}:")?;
+                }
+                if matches!(self, QueryNode::Phrase { .. }) {
+                    write!(f, "\"{text}\"")
+                } else {
+                    f.write_str(text)
+                }
+            }
+            QueryNode::And(children) => {
+                f.write_str(&children.iter().map(child).collect::<Vec<_>>().join(" AND "))
+            }
+            QueryNode::Or(children) => {
+                f.write_str(&children.iter().map(child).collect::<Vec<_>>().join(" OR "))
+            }
+            QueryNode::Not(inner) => write!(f, "NOT {}", child(inner)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Leaf {
+        text: String,
+        field: Field,
+        phrase: bool,
+    },
+}
+
+pub fn parse_query(input: &str) -> Result<QueryNode, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("empty query".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let node = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("unexpected {} in query", describe(token)));
+    }
+    Ok(node)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '-' if chars.get(i + 1).is_some_and(|n| !n.is_whitespace()) => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '"' => {
+                let (text, next) = read_phrase(&chars, i)?;
+                tokens.push(Token::Leaf {
+                    text,
+                    field: Field::Any,
+                    phrase: true,
+                });
+                i = next;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()\"".contains(chars[i]) {
+                    i += 1;
+                }
+                let word = chars[start..i].iter().collect::<String>();
+                let tagged = word
+                    .split_once(':')
+                    .and_then(|(tag, rest)| Field::from_tag(tag).map(|f| (f, rest.to_string())));
+                match (word.as_str(), tagged) {
+                    ("AND", _) => tokens.push(Token::And),
+                    ("OR", _) => tokens.push(Token::Or),
+                    ("NOT", _) => tokens.push(Token::Not),
+                    (_, Some((field, rest))) if rest.is_empty() && chars.get(i) == Some(&'"') => {
+                        let (text, next) = read_phrase(&chars, i)?;
+                        tokens.push(Token::Leaf {
+                            text,
+                            field,
+                            phrase: true,
+                        });
+                        i = next;
+                    }
+                    (_, Some((_, rest))) if rest.is_empty() => {
+                        return Err(format!("field tag '{word}' has no term"));
+                    }
+                    (_, Some((field, rest))) => tokens.push(Token::Leaf {
+                        text: rest,
+                        field,
+                        phrase: false,
+                    }),
+                    _ => tokens.push(Token::Leaf {
+                        text: word,
+                        field: Field::Any,
+                        phrase: false,
+                    }),
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a `"..."` phrase starting at the opening quote; returns the text and the index after it.
+fn read_phrase(chars: &[char], open: usize) -> Result<(String, usize), String> {
+    let close = chars[open + 1..]
+        .iter()
+        .position(|c| *c == '"')
+        .map(|p| open + 1 + p)
+        .ok_or_else(|| "unterminated quoted phrase".to_string())?;
+    let text = chars[open + 1..close]
+        .iter()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return Err("empty quoted phrase".to_string());
+    }
+    Ok((text, close + 1))
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+        Token::And => "AND".to_string(),
+        Token::Or => "OR".to_string(),
+        Token::Not => "NOT".to_string(),
+        Token::Leaf { text, .. } => format!("'{text}'"),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode, String> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            items.push(self.parse_and()?);
+        }
+        Ok(collapse(items, false))
+    }
+
+    fn parse_and(&mut self) -> Result<QueryNode, String> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                    items.push(self.parse_unary()?);
+                }
+                None | Some(Token::Or) | Some(Token::RParen) => break,
+                Some(_) => items.push(self.parse_unary()?),
+            }
+        }
+        Ok(collapse(items, true))
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(QueryNode::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<QueryNode, String> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("missing ')' in query".to_string()),
+                }
+            }
+            Some(Token::Leaf {
+                text,
+                field,
+                phrase,
+            }) => Ok(if phrase {
+                QueryNode::Phrase { text, field }
+            } else {
+                QueryNode::Term { text, field }
+            }),
+            Some(token) => Err(format!("unexpected {} in query", describe(&token))),
+            None => Err("query ends where a term was expected".to_string()),
+        }
+    }
+}
+
+/// Builds an AND (or OR) node, flattening nested nodes of the same kind and single children.
+fn collapse(items: Vec<QueryNode>, is_and: bool) -> QueryNode {
+    if items.len() == 1 {
+        return items.into_iter().next().expect("one item");
+    }
+    let mut flat = Vec::new();
+    for item in items {
+        match item {
+            QueryNode::And(inner) if is_and => flat.extend(inner),
+            QueryNode::Or(inner) if !is_and => flat.extend(inner),
+            other => flat.push(other),
+        }
+    }
+    if is_and {
+        QueryNode::And(flat)
+    } else {
+        QueryNode::Or(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Field, QueryNode, parse_query};
+
+    #[test]
+    fn parses_groups_phrases_fields_and_exclusions() {
+        let q = parse_query(r#"(aneurysm OR "intracranial aneurysm") clipping -coiling title:outcome mesh:"Microsurgery""#)
+            .expect("parse");
+        assert_eq!(
+            q.to_string(),
+            r#"(aneurysm OR "intracranial aneurysm") AND clipping AND NOT coiling AND title:outcome AND mesh:"Microsurgery""#
+        );
+        let QueryNode::And(parts) = &q else {
+            panic!("expected AND");
+        };
+        assert_eq!(
+            parts[4],
+            QueryNode::Phrase {
+                text: "Microsurgery".into(),
+                field: Field::Mesh
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_queries() {
+        assert!(parse_query("").is_err());
+        assert!(parse_query("(aneurysm OR").is_err());
+        assert!(parse_query("\"unclosed").is_err());
+        assert!(parse_query("title: aneurysm").is_err());
+        assert!(parse_query("aneurysm )").is_err());
+    }
+
+    #[test]
+    fn evaluates_against_record_text() {
+        let q = parse_query("tranexamic subdural NOT title:pediatric").expect("parse");
+        assert!(q.matches("Tranexamic acid after chronic subdural drainage", "", &[]));
+        assert!(!q.matches("Tranexamic acid for pediatric subdural hematoma", "", &[]));
+    }
+}
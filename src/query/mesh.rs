@@ -0,0 +1,322 @@
+use crate::db::mesh_repo::MeshRepo;
+use crate::domain::mesh::{MeshDescriptor, normalize_term, reading_order};
+use crate::errors::{LitError, Result};
+use crate::query::ast::{Field, QueryNode};
+use crate::types::ExpandedTerm;
+use quick_xml::events::Event;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Caps keep an expanded query within what the search APIs accept.
+const MAX_ENTRY_TERMS: usize = 8;
+const MAX_NARROWER: usize = 10;
+/// Longest run of adjacent query words tried as one heading ("intracranial aneurysm").
+const MAX_SPAN: usize = 4;
+
+/// Reads an NLM descriptor dump: `desc20xx.xml` or the ASCII `d20xx.bin` format, detected
+/// from the first non-blank byte.
+pub fn read_descriptors(path: &Path) -> Result<Vec<MeshDescriptor>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let is_xml = reader
+        .fill_buf()?
+        .iter()
+        .find(|b| !b.is_ascii_whitespace() && ![0xEF, 0xBB, 0xBF].contains(*b))
+        == Some(&b'<');
+    let descriptors = if is_xml {
+        parse_xml(reader)?
+    } else {
+        parse_ascii(reader)?
+    };
+    if descriptors.is_empty() {
+        return Err(LitError::Validation(format!(
+            "no MeSH descriptors found in {}",
+            path.display()
+        )));
+    }
+    Ok(descriptors)
+}
+
+/// ASCII format: `*NEWRECORD` blocks of `KEY = value` lines (`MH`, `ENTRY`, `PRINT ENTRY`,
+/// `MN`, `UI`). Entry values carry `|`-separated metadata after the term.
+pub fn parse_ascii<R: BufRead>(reader: R) -> Result<Vec<MeshDescriptor>> {
+    let mut out = Vec::new();
+    let mut current: Option<MeshDescriptor> = None;
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end();
+        if line == "*NEWRECORD" {
+            out.extend(current.take().and_then(finish));
+            current = Some(empty_descriptor());
+            continue;
+        }
+        let (Some(d), Some((key, value))) = (current.as_mut(), line.split_once(" = ")) else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "MH" => d.name = value.to_string(),
+            "UI" => d.ui = value.to_string(),
+            "MN" => d.tree_numbers.push(value.to_string()),
+            "ENTRY" | "PRINT ENTRY" => {
+                let term = value.split('|').next().unwrap_or_default().trim();
+                if !term.is_empty() {
+                    d.entry_terms.push(term.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    out.extend(current.and_then(finish));
+    Ok(out)
+}
+
+/// XML format, streamed so the full ~300 MB dump is never held in memory.
+pub fn parse_xml<R: BufRead>(reader: R) -> Result<Vec<MeshDescriptor>> {
+    let xml_err = |e: &dyn std::fmt::Display| LitError::Validation(format!("MeSH XML: {e}"));
+    let mut reader = quick_xml::Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut text = String::new();
+    let mut current: Option<MeshDescriptor> = None;
+    let mut out = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf).map_err(|e| xml_err(&e))? {
+            Event::Start(e) => {
+                let name = e.name().as_ref().to_vec();
+                if name == b"DescriptorRecord" {
+                    current = Some(empty_descriptor());
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(t) => text.push_str(&t.decode().map_err(|e| xml_err(&e))?),
+            Event::GeneralRef(r) => {
+                text.push('&');
+                text.push_str(&r.decode().map_err(|e| xml_err(&e))?);
+                text.push(';');
+            }
+            Event::End(_) => {
+                if let Some(d) = current.as_mut() {
+                    let value = quick_xml::escape::unescape(text.trim())
+                        .map(|v| v.into_owned())
+                        .unwrap_or_else(|_| text.trim().to_string());
+                    assign_xml_field(d, &path, value);
+                }
+                if path.last().is_some_and(|n| n == b"DescriptorRecord") {
+                    out.extend(current.take().and_then(finish));
+                }
+                path.pop();
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(out)
+}
+
+fn assign_xml_field(d: &mut MeshDescriptor, path: &[Vec<u8>], value: String) {
+    let ends_with = |tail: &[&[u8]]| {
+        path.len() >= tail.len()
+            && path[path.len() - tail.len()..]
+                .iter()
+                .zip(tail)
+                .all(|(a, b)| a.as_slice() == *b)
+    };
+    if ends_with(&[b"DescriptorRecord", b"DescriptorUI"]) {
+        d.ui = value;
+    } else if ends_with(&[b"DescriptorRecord", b"DescriptorName", b"String"]) {
+        d.name = value;
+    } else if ends_with(&[b"DescriptorRecord", b"TreeNumberList", b"TreeNumber"]) {
+        d.tree_numbers.push(value);
+    } else if ends_with(&[b"Concept", b"TermList", b"Term", b"String"]) {
+        d.entry_terms.push(value);
+    }
+}
+
+fn empty_descriptor() -> MeshDescriptor {
+    MeshDescriptor {
+        ui: String::new(),
+        name: String::new(),
+        tree_numbers: vec![],
+        entry_terms: vec![],
+    }
+}
+
+/// Drops records without a UI or heading (e.g. qualifier or supplementary records).
+fn finish(mut d: MeshDescriptor) -> Option<MeshDescriptor> {
+    if d.ui.is_empty() || d.name.is_empty() {
+        return None;
+    }
+    let mut seen = HashSet::new();
+    d.entry_terms.retain(|t| seen.insert(normalize_term(t)));
+    Some(d)
+}
+
+/// Expands query terms that match a MeSH heading or entry term into an OR of the original
+/// text, the heading as a `mesh:` term, its entry terms and its direct narrower descriptors.
+/// Exclusions are left untouched.
+pub fn expand(node: &QueryNode, repo: &MeshRepo) -> Result<(QueryNode, Vec<ExpandedTerm>)> {
+    let mut terms = Vec::new();
+    let expanded = expand_node(node, repo, &mut terms)?;
+    Ok((expanded, terms))
+}
+
+fn expand_node(
+    node: &QueryNode,
+    repo: &MeshRepo,
+    terms: &mut Vec<ExpandedTerm>,
+) -> Result<QueryNode> {
+    match node {
+        QueryNode::Term { text, field } | QueryNode::Phrase { text, field } => {
+            if matches!(field, Field::Title | Field::Abstract) || text.ends_with('*') {
+                return Ok(node.clone());
+            }
+            match repo.find_by_term(text)? {
+                Some(descriptor) => {
+                    expand_match(node.clone(), text, *field, &descriptor, repo, terms)
+                }
+                None => Ok(node.clone()),
+            }
+        }
+        QueryNode::And(children) => {
+            let mut out = Vec::new();
+            let mut i = 0;
+            while i < children.len() {
+                if let Some((len, descriptor)) = longest_heading_at(children, i, repo)? {
+                    let span = &children[i..i + len];
+                    let text = span
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let original = QueryNode::And(span.to_vec());
+                    out.push(expand_match(
+                        original,
+                        &text,
+                        Field::Any,
+                        &descriptor,
+                        repo,
+                        terms,
+                    )?);
+                    i += len;
+                } else {
+                    out.push(expand_node(&children[i], repo, terms)?);
+                    i += 1;
+                }
+            }
+            Ok(if out.len() == 1 {
+                out.remove(0)
+            } else {
+                QueryNode::And(out)
+            })
+        }
+        QueryNode::Or(children) => Ok(QueryNode::Or(
+            children
+                .iter()
+                .map(|c| expand_node(c, repo, terms))
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        QueryNode::Not(_) => Ok(node.clone()),
+    }
+}
+
+/// Longest run (2..=MAX_SPAN) of plain adjacent words starting at `start` that names a
+/// descriptor; single words are handled by `expand_node`.
+fn longest_heading_at(
+    children: &[QueryNode],
+    start: usize,
+    repo: &MeshRepo,
+) -> Result<Option<(usize, MeshDescriptor)>> {
+    let run = children[start..]
+        .iter()
+        .take(MAX_SPAN)
+        .take_while(|c| {
+            matches!(
+                c,
+                QueryNode::Term {
+                    field: Field::Any,
+                    ..
+                }
+            )
+        })
+        .count();
+    for len in (2..=run).rev() {
+        let text = children[start..start + len]
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(descriptor) = repo.find_by_term(&text)? {
+            return Ok(Some((len, descriptor)));
+        }
+    }
+    Ok(None)
+}
+
+fn expand_match(
+    original: QueryNode,
+    matched: &str,
+    field: Field,
+    descriptor: &MeshDescriptor,
+    repo: &MeshRepo,
+    terms: &mut Vec<ExpandedTerm>,
+) -> Result<QueryNode> {
+    let mut seen = HashSet::from([normalize_term(matched)]);
+    let mut alternatives = vec![original];
+    if field != Field::Mesh || normalize_term(matched) != normalize_term(&descriptor.name) {
+        alternatives.push(leaf(&descriptor.name, Field::Mesh));
+    }
+
+    // A `mesh:` term already names the heading, so only its narrower headings are added.
+    let entry_terms = if field == Field::Mesh {
+        vec![]
+    } else {
+        std::iter::once(&descriptor.name)
+            .chain(&descriptor.entry_terms)
+            .map(|t| reading_order(t))
+            .filter(|t| seen.insert(normalize_term(t)))
+            .take(MAX_ENTRY_TERMS)
+            .collect::<Vec<_>>()
+    };
+    let narrower = repo
+        .narrower(descriptor)?
+        .into_iter()
+        .map(|d| d.name)
+        .take(MAX_NARROWER)
+        .collect::<Vec<_>>();
+
+    alternatives.extend(entry_terms.iter().map(|t| leaf(t, Field::Any)));
+    let narrower_field = if field == Field::Mesh {
+        Field::Mesh
+    } else {
+        Field::Any
+    };
+    alternatives.extend(narrower.iter().map(|t| leaf(t, narrower_field)));
+
+    terms.push(ExpandedTerm {
+        matched: matched.to_string(),
+        descriptor_ui: descriptor.ui.clone(),
+        descriptor: descriptor.name.clone(),
+        entry_terms,
+        narrower,
+    });
+    Ok(QueryNode::Or(alternatives))
+}
+
+fn leaf(text: &str, field: Field) -> QueryNode {
+    if text.contains(char::is_whitespace) {
+        QueryNode::Phrase {
+            text: text.to_string(),
+            field,
+        }
+    } else {
+        QueryNode::Term {
+            text: text.to_string(),
+            field,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod ast;
+pub mod mesh;
+pub mod render;
+pub mod window;
@@ -0,0 +1,200 @@
+use crate::query::ast::{Field, QueryNode};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Query syntax a connector accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dialect {
+    PubMed,
+    EuropePmc,
+    OpenAlex,
+    ClinicalTrials,
+    /// Bag of words with no operators or fields (Crossref `query.bibliographic`).
+    FreeText,
+}
+
+/// A query rendered for one source, with notes on anything the source cannot express.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Translation {
+    pub query: String,
+    pub notes: Vec<String>,
+}
+
+pub fn render(node: &QueryNode, dialect: Dialect) -> Translation {
+    let mut notes = Vec::new();
+    let query = if dialect == Dialect::FreeText {
+        render_free_text(node, &mut notes)
+    } else {
+        render_boolean(node, dialect, &mut notes)
+    };
+    // The same note can come from leaves far apart in the tree; keep the first of each.
+    let mut seen = HashSet::new();
+    notes.retain(|note| seen.insert(note.clone()));
+    Translation { query, notes }
+}
+
+fn render_boolean(node: &QueryNode, dialect: Dialect, notes: &mut Vec<String>) -> String {
+    match node {
+        QueryNode::Term { text, field } => leaf(dialect, text, false, *field, notes),
+        QueryNode::Phrase { text, field } => leaf(dialect, text, true, *field, notes),
+        QueryNode::Or(children) => children
+            .iter()
+            .map(|c| grouped(c, dialect, notes))
+            .collect::<Vec<_>>()
+            .join(" OR "),
+        // Every supported engine treats NOT as binary ("a NOT b"), so exclusions go last.
+        QueryNode::And(children) => {
+            let (excluded, included): (Vec<_>, Vec<_>) = children
+                .iter()
+                .partition(|c| matches!(c, QueryNode::Not(_)));
+            let mut out = included
+                .iter()
+                .map(|c| grouped(c, dialect, notes))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            for node in excluded {
+                let QueryNode::Not(inner) = node else {
+                    continue;
+                };
+                let rendered = grouped(inner, dialect, notes);
+                out = if out.is_empty() {
+                    notes.push(
+                        "query has only exclusions; most sources need a positive term".to_string(),
+                    );
+                    format!("NOT {rendered}")
+                } else {
+                    format!("{out} NOT {rendered}")
+                };
+            }
+            out
+        }
+        QueryNode::Not(inner) => {
+            notes.push("query has only exclusions; most sources need a positive term".to_string());
+            format!("NOT {}", grouped(inner, dialect, notes))
+        }
+    }
+}
+
+fn grouped(node: &QueryNode, dialect: Dialect, notes: &mut Vec<String>) -> String {
+    match node {
+        QueryNode::And(c) | QueryNode::Or(c) if c.len() > 1 => {
+            format!("({})", render_boolean(node, dialect, notes))
+        }
+        _ => render_boolean(node, dialect, notes),
+    }
+}
+
+fn leaf(
+    dialect: Dialect,
+    text: &str,
+    phrase: bool,
+    field: Field,
+    notes: &mut Vec<String>,
+) -> String {
+    let quoted = if phrase || (field == Field::Mesh && text.contains(' ')) {
+        format!("\"{text}\"")
+    } else {
+        text.to_string()
+    };
+    match (dialect, field) {
+        (_, Field::Any) => quoted,
+        (Dialect::PubMed, Field::Title) => format!("{quoted}[ti]"),
+        (Dialect::PubMed, Field::Abstract) => format!("{quoted}[ab]"),
+        (Dialect::PubMed, Field::Mesh) => format!("{quoted}[mh]"),
+        (Dialect::EuropePmc, Field::Title) => format!("TITLE:{quoted}"),
+        (Dialect::EuropePmc, Field::Abstract) => format!("ABSTRACT:{quoted}"),
+        (Dialect::EuropePmc, Field::Mesh) => format!("MESH:{quoted}"),
+        (Dialect::ClinicalTrials, Field::Title) => format!("AREA[BriefTitle]{quoted}"),
+        (Dialect::ClinicalTrials, Field::Abstract) => format!("AREA[BriefSummary]{quoted}"),
+        (Dialect::ClinicalTrials, Field::Mesh) => format!("AREA[ConditionMeshTerm]{quoted}"),
+        (_, field) => {
+            notes.push(format!("{field:?} field tags are searched as plain text").to_lowercase());
+            quoted
+        }
+    }
+}
+
+fn render_free_text(node: &QueryNode, notes: &mut Vec<String>) -> String {
+    if has_structure(node, |n| matches!(n, QueryNode::Not(_))) {
+        notes.push("exclusions dropped (no NOT support)".to_string());
+    }
+    if has_structure(node, |n| matches!(n, QueryNode::Or(_))) {
+        notes.push("OR groups flattened into one bag of words".to_string());
+    }
+    if has_structure(
+        node,
+        |n| matches!(n, QueryNode::Term { field, .. } | QueryNode::Phrase { field, .. } if *field != Field::Any),
+    ) {
+        notes.push("field tags dropped".to_string());
+    }
+    node.positive_leaves()
+        .iter()
+        .filter_map(|leaf| match leaf {
+            QueryNode::Term { text, .. } | QueryNode::Phrase { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn has_structure(node: &QueryNode, pred: fn(&QueryNode) -> bool) -> bool {
+    pred(node)
+        || match node {
+            QueryNode::And(c) | QueryNode::Or(c) => c.iter().any(|n| has_structure(n, pred)),
+            QueryNode::Not(inner) => has_structure(inner, pred),
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialect, render};
+    use crate::query::ast::parse_query;
+
+    #[test]
+    fn renders_each_dialect() {
+        let q = parse_query(
+            r#"(aneurysm OR "intracranial aneurysm") title:clipping -coiling mesh:Microsurgery"#,
+        )
+        .expect("parse");
+        assert_eq!(
+            render(&q, Dialect::PubMed).query,
+            r#"(aneurysm OR "intracranial aneurysm") AND clipping[ti] AND Microsurgery[mh] NOT coiling"#
+        );
+        assert_eq!(
+            render(&q, Dialect::EuropePmc).query,
+            r#"(aneurysm OR "intracranial aneurysm") AND TITLE:clipping AND MESH:Microsurgery NOT coiling"#
+        );
+        assert_eq!(
+            render(&q, Dialect::ClinicalTrials).query,
+            r#"(aneurysm OR "intracranial aneurysm") AND AREA[BriefTitle]clipping AND AREA[ConditionMeshTerm]Microsurgery NOT coiling"#
+        );
+        let openalex = render(&q, Dialect::OpenAlex);
+        assert_eq!(
+            openalex.query,
+            r#"(aneurysm OR "intracranial aneurysm") AND clipping AND Microsurgery NOT coiling"#
+        );
+        assert_eq!(openalex.notes.len(), 2);
+
+        let crossref = render(&q, Dialect::FreeText);
+        assert_eq!(
+            crossref.query,
+            "aneurysm intracranial aneurysm clipping Microsurgery"
+        );
+        assert_eq!(crossref.notes.len(), 3);
+    }
+
+    #[test]
+    fn notes_are_listed_once_however_far_apart() {
+        let q = parse_query("title:a x title:b abstract:c").expect("parse");
+        let notes = render(&q, Dialect::OpenAlex).notes;
+        assert_eq!(
+            notes,
+            vec![
+                "title field tags are searched as plain text",
+                "abstract field tags are searched as plain text",
+            ]
+        );
+    }
+}
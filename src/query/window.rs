@@ -0,0 +1,178 @@
+use chrono::{Duration, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Publication date window parsed from `--since`.
+///
+/// Accepted forms: `30d`, `6w`, `6m`, `2y` (relative to today), `2020-01-01` or `2019`
+/// (open-ended start), `2019..2023`, `2024-01-01..2024-06-30`, `2020..` / `..2020`, and `all`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateWindow {
+    pub raw: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateWindow {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        Self::parse_at(raw, Utc::now().date_naive())
+    }
+
+    /// Parses relative forms against `today`, so tests stay deterministic.
+    pub fn parse_at(raw: &str, today: NaiveDate) -> Result<Self, String> {
+        let input = raw.trim();
+        if input.is_empty() {
+            return Err("empty date window".to_string());
+        }
+        let (from, to) = if input.eq_ignore_ascii_case("all") {
+            (None, None)
+        } else if let Some((start, end)) = input.split_once("..") {
+            let from = parse_bound(start, Bound::Start)?;
+            let to = parse_bound(end, Bound::End)?;
+            if from.is_none() && to.is_none() {
+                return Err(format!("date range '{input}' has no bounds"));
+            }
+            (from, to)
+        } else if let Some(from) = parse_relative(input, today)? {
+            (Some(from), None)
+        } else {
+            (parse_bound(input, Bound::Start)?, None)
+        };
+
+        if let (Some(f), Some(t)) = (from, to)
+            && f > t
+        {
+            return Err(format!("date range '{input}' ends before it starts"));
+        }
+        Ok(Self {
+            raw: input.to_string(),
+            from,
+            to,
+        })
+    }
+
+    /// Re-resolves relative forms such as `30d` against `today`; fixed dates are unchanged.
+    pub fn rebased(&self, today: NaiveDate) -> Self {
+        Self::parse_at(&self.raw, today).unwrap_or_else(|_| self.clone())
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+}
+
+impl FromStr for DateWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for DateWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Start,
+    End,
+}
+
+fn parse_relative(input: &str, today: NaiveDate) -> Result<Option<NaiveDate>, String> {
+    let Some(unit) = input.chars().last().filter(|c| c.is_ascii_alphabetic()) else {
+        return Ok(None);
+    };
+    let count = input[..input.len() - 1]
+        .parse::<u32>()
+        .map_err(|_| format!("invalid relative window '{input}'"))?;
+    if count == 0 {
+        return Err(format!("relative window '{input}' must be at least 1"));
+    }
+    let from = match unit.to_ascii_lowercase() {
+        'd' => today.checked_sub_signed(Duration::days(count as i64)),
+        'w' => today.checked_sub_signed(Duration::weeks(count as i64)),
+        'm' => today.checked_sub_months(Months::new(count)),
+        'y' => today.checked_sub_months(Months::new(count.saturating_mul(12))),
+        _ => return Err(format!("unknown window unit '{unit}' in '{input}' (use d, w, m or y)")),
+    };
+    from.map(Some)
+        .ok_or_else(|| format!("relative window '{input}' is out of range"))
+}
+
+fn parse_bound(raw: &str, bound: Bound) -> Result<Option<NaiveDate>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return Ok(Some(date));
+    }
+    if raw.len() == 4
+        && let Ok(year) = raw.parse::<i32>()
+    {
+        let date = match bound {
+            Bound::Start => NaiveDate::from_ymd_opt(year, 1, 1),
+            Bound::End => NaiveDate::from_ymd_opt(year, 12, 31),
+        };
+        return date
+            .map(Some)
+            .ok_or_else(|| format!("invalid year '{raw}'"));
+    }
+    Err(format!(
+        "invalid date '{raw}' (expected YYYY-MM-DD, YYYY, or a relative window like 30d/6m/2y)"
+    ))
+}
+
+/// Last day covered by the window; open-ended windows run to `today`.
+pub fn end_or_today(window: &DateWindow, today: NaiveDate) -> NaiveDate {
+    window.to.unwrap_or(today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateWindow;
+    use chrono::NaiveDate;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).expect("date")
+    }
+
+    #[test]
+    fn parses_relative_windows() {
+        let today = d(2024, 6, 30);
+        assert_eq!(DateWindow::parse_at("30d", today).unwrap().from, Some(d(2024, 5, 31)));
+        assert_eq!(DateWindow::parse_at("2w", today).unwrap().from, Some(d(2024, 6, 16)));
+        assert_eq!(DateWindow::parse_at("6m", today).unwrap().from, Some(d(2023, 12, 30)));
+        assert_eq!(DateWindow::parse_at("2y", today).unwrap().from, Some(d(2022, 6, 30)));
+    }
+
+    #[test]
+    fn parses_absolute_dates_and_ranges() {
+        let today = d(2024, 6, 30);
+        let single = DateWindow::parse_at("2020-01-01", today).unwrap();
+        assert_eq!((single.from, single.to), (Some(d(2020, 1, 1)), None));
+
+        let years = DateWindow::parse_at("2019..2023", today).unwrap();
+        assert_eq!((years.from, years.to), (Some(d(2019, 1, 1)), Some(d(2023, 12, 31))));
+
+        let dates = DateWindow::parse_at("2024-01-01..2024-06-30", today).unwrap();
+        assert_eq!((dates.from, dates.to), (Some(d(2024, 1, 1)), Some(d(2024, 6, 30))));
+
+        let open = DateWindow::parse_at("..2010", today).unwrap();
+        assert_eq!((open.from, open.to), (None, Some(d(2010, 12, 31))));
+
+        assert!(DateWindow::parse_at("all", today).unwrap().is_unbounded());
+    }
+
+    #[test]
+    fn rejects_malformed_windows() {
+        let today = d(2024, 6, 30);
+        for bad in ["", "30x", "0d", "abc", "2024-13-01", "2023..2019", ".."] {
+            assert!(DateWindow::parse_at(bad, today).is_err(), "{bad} should fail");
+        }
+    }
+}
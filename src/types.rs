@@ -1,26 +1,108 @@
-use chrono::{DateTime, Utc};
+use crate::domain::doc::{MeshHeading, PreprintInfo};
+use crate::domain::trial::TrialRecord;
+use crate::query::window::DateWindow;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchResult {
     pub source: String,
     pub doi: Option<String>,
     pub pmid: Option<String>,
+    #[serde(default)]
+    pub pmcid: Option<String>,
     pub title: String,
     pub journal: Option<String>,
     pub year: Option<i32>,
     pub authors: Vec<String>,
     pub abstract_text: Option<String>,
+    #[serde(default)]
+    pub mesh_headings: Vec<MeshHeading>,
+    #[serde(default)]
+    pub publication_types: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
     pub oa_url: Option<String>,
     pub epmc_id: Option<String>,
     pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trial: Option<TrialRecord>,
+    /// Registry ids the source lists for this record (e.g. PubMed DataBank accessions).
+    #[serde(default)]
+    pub trial_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preprint: Option<PreprintInfo>,
+    /// Every connector that returned this work, in arrival order.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Field name -> connector whose value was kept when duplicates were fused.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_sources: BTreeMap<String, String>,
+    /// Reciprocal rank fusion of per-source ranks and lexical relevance; higher ranks first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fused_score: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchOutput {
     pub query: String,
+    #[serde(default)]
+    pub since: Option<DateWindow>,
+    /// Day relative `since` windows were resolved against; offline re-runs reuse it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_on: Option<NaiveDate>,
     pub generated_at: DateTime<Utc>,
     pub results: Vec<SearchResult>,
+    #[serde(default)]
+    pub source_status: Vec<SourceStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expansion: Option<QueryExpansion>,
+}
+
+/// MeSH expansion applied to the query before it was rendered for each source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryExpansion {
+    /// Descriptor dump the expansion came from, with its load time.
+    pub mesh_source: String,
+    pub expanded_query: String,
+    pub terms: Vec<ExpandedTerm>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpandedTerm {
+    /// Query text that matched a descriptor heading or entry term.
+    pub matched: String,
+    pub descriptor_ui: String,
+    pub descriptor: String,
+    pub entry_terms: Vec<String>,
+    pub narrower: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceState {
+    Ok,
+    Failed,
+    Partial,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStatus {
+    pub source: String,
+    /// The query as sent to this source, after translation.
+    #[serde(default)]
+    pub rendered_query: Option<String>,
+    pub status: SourceState,
+    pub count: usize,
+    #[serde(default)]
+    pub total_available: Option<u64>,
+    #[serde(default)]
+    pub since_applied: bool,
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    pub latency_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,4 +112,7 @@ pub struct QaReport {
     pub oa_retrieval_rate: f64,
     pub extraction_success_rate: f64,
     pub unanchored_claim_count: usize,
+    /// Briefs citing a retracted doc, as `slug: doc_id` entries.
+    #[serde(default)]
+    pub retracted_citations: Vec<String>,
 }
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use lit::brief::compose::{BriefCitation, BriefJson, BriefTakeaway};
-use lit::brief::validate::validate_brief;
-use lit::domain::evidence::{AnchorType, ClaimType, EvidenceLedgerRow};
+use lit::brief::validate::{retracted_citations, validate_brief};
+use lit::domain::evidence::{AnchorType, ClaimType, EvidenceLedgerRow, RegistryAnchor};
 
 fn ledger_row() -> EvidenceLedgerRow {
     EvidenceLedgerRow {
@@ -17,6 +17,7 @@ fn ledger_row() -> EvidenceLedgerRow {
         anchor_type: AnchorType::Pdf,
         page_number: Some(5),
         section_heading: None,
+        registry: None,
         anchor_quote: "The randomized trial showed lower complication rates".into(),
         claim_text: "The randomized trial showed lower complication rates".into(),
         claim_type: ClaimType::Outcome,
@@ -43,9 +44,11 @@ fn brief_validator_accepts_valid_citations() {
             anchor_type: "pdf".into(),
             page_number: Some(5),
             section_heading: None,
+            registry: None,
             anchor_quote: "The randomized trial showed lower complication rates".into(),
         }],
         key_figures: vec![],
+        trials: vec![],
     };
 
     let res = validate_brief(&brief, &[ledger_row()]);
@@ -70,11 +73,103 @@ fn brief_validator_rejects_anchor_mismatch() {
             anchor_type: "pdf".into(),
             page_number: Some(99),
             section_heading: None,
+            registry: None,
             anchor_quote: "Wrong quote".into(),
         }],
         key_figures: vec![],
+        trials: vec![],
     };
 
     let res = validate_brief(&brief, &[ledger_row()]);
     assert!(res.is_err());
 }
+
+#[test]
+fn brief_validator_checks_registry_anchor() {
+    let anchor = RegistryAnchor {
+        nct_id: "NCT01234567".into(),
+        module: "outcomeMeasuresModule".into(),
+        index: 0,
+        measure_title: "Recurrence requiring surgery".into(),
+        time_frame: Some("6 months".into()),
+    };
+    let ledger = EvidenceLedgerRow {
+        source_type: "registry".into(),
+        anchor_type: AnchorType::Registry,
+        page_number: None,
+        registry: Some(anchor.clone()),
+        anchor_quote: "TXA: 12 participants; Placebo: 21 participants".into(),
+        ..ledger_row()
+    };
+    let citation = BriefCitation {
+        claim_id: "claim_1".into(),
+        doc_id: "doc_1".into(),
+        doi: None,
+        pmid: None,
+        anchor_type: "registry".into(),
+        page_number: None,
+        section_heading: None,
+        registry: Some(anchor.clone()),
+        anchor_quote: "TXA: 12 participants; Placebo: 21 participants".into(),
+    };
+    let brief = BriefJson {
+        slug: "x".into(),
+        query: "neurosurgery".into(),
+        generated_at: Utc::now(),
+        takeaways: vec![BriefTakeaway {
+            text: "Fewer recurrences with TXA".into(),
+            citation_ids: vec!["claim_1".into()],
+        }],
+        citations: vec![citation.clone()],
+        key_figures: vec![],
+        trials: vec![],
+    };
+    assert!(validate_brief(&brief, std::slice::from_ref(&ledger)).is_ok());
+
+    let wrong_measure = BriefJson {
+        citations: vec![BriefCitation {
+            registry: Some(RegistryAnchor {
+                index: 1,
+                time_frame: Some("12 months".into()),
+                ..anchor
+            }),
+            ..citation
+        }],
+        ..brief
+    };
+    assert!(validate_brief(&wrong_measure, &[ledger]).is_err());
+}
+
+#[test]
+fn retracted_citations_lists_cited_retracted_docs() {
+    let citation = BriefCitation {
+        claim_id: "claim_1".into(),
+        doc_id: "doc_1".into(),
+        doi: None,
+        pmid: None,
+        anchor_type: "pdf".into(),
+        page_number: Some(5),
+        section_heading: None,
+        registry: None,
+        anchor_quote: "quote".into(),
+    };
+    let brief = BriefJson {
+        slug: "x".into(),
+        query: "neurosurgery".into(),
+        generated_at: Utc::now(),
+        takeaways: vec![],
+        citations: vec![
+            citation.clone(),
+            BriefCitation {
+                claim_id: "claim_2".into(),
+                doc_id: "doc_2".into(),
+                ..citation
+            },
+        ],
+        key_figures: vec![],
+        trials: vec![],
+    };
+    let retracted = ["doc_2".to_string(), "doc_9".to_string()].into_iter().collect();
+    assert_eq!(retracted_citations(&brief, &retracted), vec!["doc_2".to_string()]);
+    assert!(retracted_citations(&brief, &Default::default()).is_empty());
+}
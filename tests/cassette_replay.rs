@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+use lit::cli::SearchArgs;
+use lit::config::{AppConfig, CassetteMode};
+use lit::connectors::{ApiClient, crossref};
+use lit::errors::LitError;
+use lit::pipeline::app::App;
+use lit::pipeline::search;
+use lit::query::window::DateWindow;
+use lit::types::{SearchOutput, SourceState};
+use serde_json::json;
+use tempfile::tempdir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn config(data: &std::path::Path, cassettes: &std::path::Path, mode: CassetteMode) -> AppConfig {
+    let mut cfg = AppConfig::load(None, Some(data)).expect("config");
+    cfg.cassette.mode = mode;
+    cfg.cassette.dir = cassettes.to_path_buf();
+    cfg.retry.max_attempts = 1;
+    cfg
+}
+
+fn client(data: &std::path::Path, cassettes: &std::path::Path, mode: CassetteMode) -> ApiClient {
+    ApiClient::new(&config(data, cassettes, mode)).expect("client")
+}
+
+#[tokio::test]
+async fn recorded_traffic_replays_without_network() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/works"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"items\":[1,2]}"))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/paper.pdf"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0x25, 0x50, 0xff, 0x00]))
+        .mount(&server)
+        .await;
+
+    let cassettes = tempdir().expect("tempdir");
+    let (record_data, replay_data) = (tempdir().expect("tempdir"), tempdir().expect("tempdir"));
+    let works = format!("{}/works?query=x&mailto=me@example.org", server.uri());
+    let pdf = format!("{}/paper.pdf", server.uri());
+
+    let recorder = client(record_data.path(), cassettes.path(), CassetteMode::Record);
+    assert_eq!(
+        recorder
+            .get_text_cached("crossref", &works)
+            .await
+            .expect("live"),
+        "{\"items\":[1,2]}"
+    );
+    // Recording bypasses the HTTP cache so a second call still reaches the server.
+    recorder
+        .get_text_cached("crossref", &works)
+        .await
+        .expect("live again");
+    let bytes = recorder
+        .download_bytes("unpaywall", &pdf)
+        .await
+        .expect("pdf");
+    drop(server);
+
+    let replayer = client(replay_data.path(), cassettes.path(), CassetteMode::Replay);
+    // Credentials are redacted from the key, so a different mailto still matches.
+    let other_mailto = works.replace("me@example.org", "ci@example.org");
+    assert_eq!(
+        replayer
+            .get_text_cached("crossref", &other_mailto)
+            .await
+            .expect("replayed"),
+        "{\"items\":[1,2]}"
+    );
+    assert_eq!(
+        replayer
+            .download_bytes("unpaywall", &pdf)
+            .await
+            .expect("replayed"),
+        bytes
+    );
+
+    let missing = replayer
+        .get_text_cached("crossref", "https://api.crossref.org/works?query=never")
+        .await
+        .expect_err("not recorded");
+    assert!(matches!(missing, LitError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn relative_windows_replay_against_the_recording_day() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/works"))
+        .and(query_param("filter", "from-pub-date:2024-05-31"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": {
+            "total-results": 1,
+            "items": [{"DOI": "10.1000/txa", "title": ["Tranexamic acid after burr hole"]}]
+        }})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Record `--since 30d` as it resolved on 2024-06-30, then date the cassette that day.
+    let recorded_on = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+    let cassettes = tempdir().expect("tempdir");
+    let (record_data, replay_data) = (tempdir().expect("tempdir"), tempdir().expect("tempdir"));
+    let mut cfg = config(record_data.path(), cassettes.path(), CassetteMode::Record);
+    cfg.endpoints.crossref = server.uri();
+    let recorder = ApiClient::new(&cfg).expect("client");
+    let since = DateWindow::parse_at("30d", recorded_on).unwrap();
+    let batch = crossref::search(&recorder, "tranexamic", 20, &since)
+        .await
+        .expect("live");
+    assert_eq!(batch.results.len(), 1);
+    std::fs::write(
+        cassettes.path().join("manifest.json"),
+        json!({"recorded_on": "2024-06-30"}).to_string(),
+    )
+    .expect("manifest");
+    let endpoint = server.uri();
+    drop(server);
+
+    // Replaying today, `30d` must still mean the window the cassette was recorded with.
+    let mut cfg = config(replay_data.path(), cassettes.path(), CassetteMode::Replay);
+    cfg.endpoints.crossref = endpoint;
+    let app = App::new(cfg).expect("app");
+    assert_eq!(app.api.today(), recorded_on);
+    search::run(
+        &app,
+        SearchArgs {
+            query: "tranexamic".into(),
+            since: Some("30d".parse().expect("window")),
+            limit: 20,
+            sources: vec!["crossref".into()],
+            out: None,
+            explain: false,
+            expand_mesh: false,
+        },
+    )
+    .await
+    .expect("replayed search");
+
+    let output: SearchOutput = serde_json::from_str(
+        &std::fs::read_to_string(app.paths.search_output_path()).expect("output"),
+    )
+    .expect("json");
+    assert_eq!(output.source_status[0].status, SourceState::Ok);
+    assert_eq!(output.results.len(), 1);
+    assert_eq!(output.since.expect("since").from, since.from);
+}
@@ -0,0 +1,44 @@
+use lit::config::AppConfig;
+use lit::connectors::registry::ConnectorRegistry;
+use tempfile::tempdir;
+
+fn registry() -> ConnectorRegistry {
+    let dir = tempdir().expect("tempdir");
+    let cfg = AppConfig::load(None, Some(dir.path())).expect("config");
+    ConnectorRegistry::from_config(&cfg)
+}
+
+#[test]
+fn resolve_defaults_to_enabled_sources() {
+    let reg = registry();
+    let names = reg
+        .resolve(&[])
+        .expect("resolve")
+        .iter()
+        .map(|c| c.name())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["pubmed", "europepmc", "crossref", "openalex", "clinicaltrials"]
+    );
+}
+
+#[test]
+fn resolve_rejects_unknown_source() {
+    let reg = registry();
+    let err = reg
+        .resolve(&["pubmed".to_string(), "pubmd".to_string()])
+        .err()
+        .expect("unknown source should fail");
+    assert!(err.to_string().contains("pubmd"));
+}
+
+#[test]
+fn resolve_is_case_insensitive_and_dedupes() {
+    let reg = registry();
+    let out = reg
+        .resolve(&["Crossref".to_string(), "crossref".to_string()])
+        .expect("resolve");
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].name(), "crossref");
+}
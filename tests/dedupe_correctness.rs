@@ -1,6 +1,8 @@
 use chrono::Utc;
 use lit::db::docs_repo::DocsRepo;
+use lit::db::trials_repo::TrialsRepo;
 use lit::db::Db;
+use lit::domain::doc::{HostType, MeshHeading, OaLocation, OaStatus, OaVersion, PreprintInfo};
 use lit::types::SearchResult;
 use tempfile::tempdir;
 
@@ -21,10 +23,7 @@ fn dedupe_by_doi() {
         journal: Some("JNS".into()),
         year: Some(2024),
         authors: vec!["A".into()],
-        abstract_text: None,
-        oa_url: None,
-        epmc_id: None,
-        url: None,
+        ..Default::default()
     };
     let b = SearchResult {
         title: "Different title".into(),
@@ -43,16 +42,10 @@ fn dedupe_by_pmid_when_doi_missing() {
     let repo = mk_repo();
     let a = SearchResult {
         source: "pubmed".into(),
-        doi: None,
         pmid: Some("44444".into()),
         title: "Trial data A".into(),
-        journal: None,
         year: Some(2022),
-        authors: vec![],
-        abstract_text: None,
-        oa_url: None,
-        epmc_id: None,
-        url: None,
+        ..Default::default()
     };
     let b = SearchResult {
         title: "Trial data B".into(),
@@ -70,16 +63,9 @@ fn dedupe_by_title_hash_and_year() {
     let repo = mk_repo();
     let a = SearchResult {
         source: "crossref".into(),
-        doi: None,
-        pmid: None,
         title: "Endoscopic skull base approach".into(),
-        journal: None,
         year: Some(2021),
-        authors: vec![],
-        abstract_text: None,
-        oa_url: None,
-        epmc_id: None,
-        url: None,
+        ..Default::default()
     };
     let b = SearchResult {
         source: "openalex".into(),
@@ -118,5 +104,225 @@ fn dedupe_by_sha256_on_local_ingest() {
     assert_eq!(d1.doc_id, d2.doc_id);
     let list = repo.list_docs().expect("list");
     assert_eq!(list.len(), 1);
-    assert_eq!(list[0].updated_at <= Utc::now(), true);
+    assert!(list[0].updated_at <= Utc::now());
+}
+
+#[test]
+fn pubmed_indexing_round_trips_and_pmcid_dedupes() {
+    let repo = mk_repo();
+    let a = SearchResult {
+        source: "pubmed".into(),
+        pmid: Some("555".into()),
+        pmcid: Some("PMC777".into()),
+        title: "Microsurgical clipping outcomes".into(),
+        year: Some(2023),
+        abstract_text: Some("BACKGROUND: text".into()),
+        mesh_headings: vec![MeshHeading {
+            descriptor: "Intracranial Aneurysm".into(),
+            qualifiers: vec!["surgery".into()],
+            major_topic: true,
+        }],
+        publication_types: vec!["Randomized Controlled Trial".into()],
+        keywords: vec!["clipping".into()],
+        ..Default::default()
+    };
+    let b = SearchResult {
+        source: "europepmc".into(),
+        pmid: None,
+        pmcid: Some("pmc777".into()),
+        title: "Another title".into(),
+        mesh_headings: vec![],
+        publication_types: vec![],
+        keywords: vec![],
+        ..a.clone()
+    };
+
+    let d1 = repo.upsert_from_search(&a).expect("insert a");
+    let d2 = repo.upsert_from_search(&b).expect("insert b");
+    assert_eq!(d1.doc_id, d2.doc_id);
+    assert_eq!(d2.publication_types, vec!["Randomized Controlled Trial".to_string()]);
+    assert_eq!(d2.mesh_headings[0].qualifiers, vec!["surgery".to_string()]);
+    assert_eq!(d2.keywords, vec!["clipping".to_string()]);
+}
+
+#[test]
+fn unpaywall_detail_survives_later_merges() {
+    let repo = mk_repo();
+    let a = SearchResult {
+        source: "crossref".into(),
+        doi: Some("10.1000/oa".into()),
+        title: "Open access neurosurgery".into(),
+        year: Some(2022),
+        ..Default::default()
+    };
+    let doc = repo.upsert_from_search(&a).expect("insert");
+    assert!(doc.access_needed);
+
+    let location = OaLocation {
+        url: Some("https://journal.example/a".into()),
+        url_for_pdf: Some("https://journal.example/a.pdf".into()),
+        license: Some("cc-by".into()),
+        version: Some(OaVersion::Published),
+        host_type: Some(HostType::Publisher),
+        evidence: Some("open (via page says license)".into()),
+        is_best: true,
+    };
+    repo.update_oa(
+        &doc.doc_id,
+        &OaStatus::Gold,
+        std::slice::from_ref(&location),
+        Some(&location),
+    )
+    .expect("update oa");
+
+    let landing_only = OaLocation {
+        url: Some("https://repo.example/landing".into()),
+        url_for_pdf: None,
+        license: None,
+        ..location.clone()
+    };
+    repo.update_oa(
+        &doc.doc_id,
+        &OaStatus::Gold,
+        std::slice::from_ref(&location),
+        Some(&landing_only),
+    )
+    .expect("landing page does not replace the pdf");
+
+    let merged = repo
+        .upsert_from_search(&SearchResult {
+            source: "openalex".into(),
+            oa_url: Some("https://repo.example/a".into()),
+            ..a
+        })
+        .expect("merge");
+    assert_eq!(merged.oa_status, OaStatus::Gold);
+    assert_eq!(merged.oa_locations, vec![location]);
+    assert_eq!(merged.license.as_deref(), Some("cc-by"));
+    assert_eq!(merged.oa_url.as_deref(), Some("https://journal.example/a.pdf"));
+    assert!(!merged.access_needed);
+}
+
+#[test]
+fn reconciled_ids_merge_duplicates_across_sources() {
+    let dir = tempdir().expect("tempdir");
+    let db = Db::open(&dir.path().join("lit.db")).expect("db open");
+    let repo = DocsRepo::new(db.clone());
+    let trials = TrialsRepo::new(db);
+
+    let crossref = SearchResult {
+        source: "crossref".into(),
+        doi: Some("10.1000/MERGE".into()),
+        title: "Endoscopic third ventriculostomy outcomes".into(),
+        journal: Some("J Neurosurg".into()),
+        year: Some(2021),
+        ..Default::default()
+    };
+    let pubmed = SearchResult {
+        source: "pubmed".into(),
+        doi: None,
+        pmid: Some("555".into()),
+        title: "Outcomes of ETV: a cohort".into(),
+        abstract_text: Some("Abstract.".into()),
+        publication_types: vec!["Journal Article".into()],
+        ..crossref.clone()
+    };
+    let a = repo.upsert_from_search(&crossref).expect("insert crossref");
+    let b = repo.upsert_from_search(&pubmed).expect("insert pubmed");
+    assert_ne!(a.doc_id, b.doc_id);
+    trials.link_doc(&b.doc_id, "NCT01234567", "pubmed_databank").expect("link");
+
+    let outcome = repo
+        .reconcile_ids(&a.doc_id, Some("10.1000/merge"), Some("555"), Some("PMC999"))
+        .expect("reconcile");
+    assert_eq!(outcome.doc_id, a.doc_id);
+    assert_eq!(outcome.merged, vec![b.doc_id.clone()]);
+    assert!(outcome.filled);
+
+    let docs = repo.list_docs().expect("list");
+    assert_eq!(docs.len(), 1);
+    let doc = &docs[0];
+    assert_eq!(doc.pmid.as_deref(), Some("555"));
+    assert_eq!(doc.pmcid.as_deref(), Some("PMC999"));
+    assert_eq!(doc.abstract_text.as_deref(), Some("Abstract."));
+    assert_eq!(doc.title, "Endoscopic third ventriculostomy outcomes");
+    assert_eq!(trials.links_for_doc(&a.doc_id).expect("links").len(), 1);
+    assert_eq!(repo.resolve_doc_id(&b.doc_id).expect("alias"), a.doc_id);
+    assert_eq!(repo.resolve_doc_id(&a.doc_id).expect("kept"), a.doc_id);
+    assert_eq!(repo.aliases().expect("aliases")[&b.doc_id], a.doc_id);
+}
+
+#[test]
+fn preprint_and_journal_version_collapse_into_one_doc() {
+    let repo = mk_repo();
+    let info = PreprintInfo {
+        server: "medrxiv".into(),
+        doi: "10.1101/2023.01.02.23284101".into(),
+        version: Some(1),
+        published_doi: None,
+        pdf_url: Some("https://www.medrxiv.org/content/10.1101/2023.01.02.23284101v1.full.pdf".into()),
+        jats_url: None,
+        license: Some("cc-by".into()),
+    };
+    let preprint = SearchResult {
+        source: "medrxiv".into(),
+        doi: Some(info.doi.clone()),
+        title: "Tranexamic acid for chronic subdural hematoma".into(),
+        year: Some(2023),
+        abstract_text: Some("Preprint abstract.".into()),
+        publication_types: vec!["Preprint".into()],
+        oa_url: info.pdf_url.clone(),
+        preprint: Some(info.clone()),
+        ..Default::default()
+    };
+    let journal = SearchResult {
+        source: "pubmed".into(),
+        doi: Some("10.1001/jamaneurol.2024.0001".into()),
+        pmid: Some("38000001".into()),
+        title: "Tranexamic acid and recurrence of chronic subdural hematoma: a randomized trial".into(),
+        journal: Some("JAMA Neurol".into()),
+        year: Some(2024),
+        publication_types: vec!["Randomized Controlled Trial".into()],
+        oa_url: None,
+        preprint: None,
+        ..preprint.clone()
+    };
+
+    let preprint_pdf = info.pdf_url.clone();
+    let early = repo.upsert_from_search(&preprint).expect("preprint");
+    assert!(early.is_preprint);
+    assert_eq!(early.oa_status, OaStatus::Green);
+    let published = repo.upsert_from_search(&journal).expect("journal");
+    assert_ne!(early.doc_id, published.doc_id);
+
+    // The server later reports the published DOI for the preprint.
+    let linked = SearchResult {
+        doi: journal.doi.clone(),
+        preprint: Some(PreprintInfo {
+            published_doi: journal.doi.clone(),
+            ..info
+        }),
+        ..preprint
+    };
+    let doc = repo.upsert_from_search(&linked).expect("link");
+
+    let docs = repo.list_docs().expect("list");
+    assert_eq!(docs.len(), 1);
+    assert_eq!(doc.doc_id, early.doc_id);
+    assert!(!doc.is_preprint);
+    assert_eq!(doc.doi.as_deref(), Some("10.1001/jamaneurol.2024.0001"));
+    assert_eq!(doc.preprint_doi.as_deref(), Some("10.1101/2023.01.02.23284101"));
+    assert_eq!(doc.pmid.as_deref(), Some("38000001"));
+    assert_eq!(doc.journal.as_deref(), Some("JAMA Neurol"));
+    assert_eq!(doc.title, journal.title);
+    // Access details describe the journal version; the preprint copy is only a location.
+    assert_eq!(doc.oa_url, None);
+    assert_ne!(doc.oa_status, OaStatus::Green);
+    assert_eq!(doc.license, None);
+    assert!(doc.access_needed);
+    assert!(!doc.publication_types.iter().any(|t| t == "Preprint"));
+    assert!(doc
+        .oa_locations
+        .iter()
+        .any(|l| l.url.as_deref() == preprint_pdf.as_deref()));
 }
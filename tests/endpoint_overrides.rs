@@ -0,0 +1,58 @@
+use lit::config::AppConfig;
+use lit::connectors::{ApiClient, crossref, epmc};
+use lit::query::window::DateWindow;
+use tempfile::tempdir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn connectors_and_full_text_links_follow_configured_endpoints() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/search"))
+        .and(query_param("query", "aspirin"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "hitCount": 1,
+            "nextCursorMark": "*",
+            "resultList": {"result": [{
+                "id": "PMC1",
+                "title": "Aspirin and stroke",
+                "isOpenAccess": "Y"
+            }]}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/works"))
+        .and(query_param("filter", "updates:10.1/x"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"message": {"items": []}})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(data.path())).expect("config");
+    cfg.retry.max_attempts = 1;
+    cfg.endpoints.europepmc = format!("{}/rest", server.uri());
+    cfg.endpoints.crossref = server.uri();
+    let client = ApiClient::new(&cfg).expect("client");
+
+    let window = DateWindow::parse("all").expect("window");
+    let batch = epmc::search(&client, "aspirin", 5, &window, client.today())
+        .await
+        .expect("search");
+    assert_eq!(batch.results.len(), 1);
+    // The stored link names Europe PMC itself; the override only applies when fetching.
+    assert_eq!(
+        batch.results[0].oa_url.as_deref(),
+        Some("https://www.ebi.ac.uk/europepmc/webservices/rest/PMC1/fullTextXML")
+    );
+
+    let updates = crossref::updates_for(&client, "10.1/x")
+        .await
+        .expect("updates");
+    assert!(updates.is_empty());
+}
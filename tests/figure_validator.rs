@@ -24,6 +24,7 @@ fn figure_validator_requires_file_and_index_row() {
             provenance: "pmid=1 page=1".into(),
             license: None,
         }],
+        trials: vec![],
     };
 
     let index = vec![FigureIndexRow {
@@ -0,0 +1,127 @@
+use lit::db::Db;
+use lit::db::mesh_repo::MeshRepo;
+use lit::query::ast::parse_query;
+use lit::query::mesh::{expand, parse_ascii, parse_xml};
+use tempfile::tempdir;
+
+const ASCII_DUMP: &str = "\
+*NEWRECORD
+RECTYPE = D
+MH = Aneurysm
+ENTRY = Aneurysms|T190|NON|EQV|UNK (19XX)|
+MN = C14.907.055
+UI = D000783
+
+*NEWRECORD
+RECTYPE = D
+MH = Intracranial Aneurysm
+PRINT ENTRY = Aneurysm, Intracranial|T047|NON|EQV|NLM (1966)|
+ENTRY = Cerebral Aneurysm|T047|NON|EQV|NLM (1991)|
+MN = C14.907.055.635
+MN = C10.228.140.300.200.200
+UI = D002532
+
+*NEWRECORD
+RECTYPE = D
+MH = Microsurgery
+MN = E04.555
+UI = D008866
+";
+
+const XML_DUMP: &str = r#"<?xml version="1.0"?>
+<DescriptorRecordSet LanguageCode="eng">
+  <DescriptorRecord DescriptorClass="1">
+    <DescriptorUI>D008866</DescriptorUI>
+    <DescriptorName><String>Microsurgery</String></DescriptorName>
+    <PharmacologicalActionList>
+      <PharmacologicalAction>
+        <DescriptorReferredTo>
+          <DescriptorUI>D000000</DescriptorUI>
+          <DescriptorName><String>Not this one</String></DescriptorName>
+        </DescriptorReferredTo>
+      </PharmacologicalAction>
+    </PharmacologicalActionList>
+    <TreeNumberList><TreeNumber>E04.555</TreeNumber></TreeNumberList>
+    <ConceptList>
+      <Concept PreferredConceptYN="Y">
+        <TermList>
+          <Term><TermUI>T1</TermUI><String>Microsurgery</String></Term>
+          <Term><TermUI>T2</TermUI><String>Microsurgical Procedures &amp; Techniques</String></Term>
+        </TermList>
+      </Concept>
+    </ConceptList>
+  </DescriptorRecord>
+</DescriptorRecordSet>
+"#;
+
+#[test]
+fn parses_both_dump_formats() {
+    let ascii = parse_ascii(ASCII_DUMP.as_bytes()).expect("ascii");
+    assert_eq!(ascii.len(), 3);
+    assert_eq!(ascii[1].name, "Intracranial Aneurysm");
+    assert_eq!(
+        ascii[1].entry_terms,
+        vec!["Aneurysm, Intracranial", "Cerebral Aneurysm"]
+    );
+    assert_eq!(ascii[1].tree_numbers.len(), 2);
+
+    let xml = parse_xml(XML_DUMP.as_bytes()).expect("xml");
+    assert_eq!(xml.len(), 1);
+    assert_eq!(xml[0].ui, "D008866");
+    assert_eq!(xml[0].name, "Microsurgery");
+    assert_eq!(xml[0].tree_numbers, vec!["E04.555"]);
+    assert_eq!(
+        xml[0].entry_terms,
+        vec!["Microsurgery", "Microsurgical Procedures & Techniques"]
+    );
+}
+
+#[test]
+fn expands_entry_terms_and_narrower_headings() {
+    let dir = tempdir().expect("tempdir");
+    let db = Db::open(&dir.path().join("lit.db")).expect("db open");
+    let repo = MeshRepo::new(db);
+    assert!(repo.source().expect("source").is_none());
+    repo.replace_all(
+        &parse_ascii(ASCII_DUMP.as_bytes()).expect("ascii"),
+        "d2025.bin",
+    )
+    .expect("load");
+    assert!(
+        repo.source()
+            .expect("source")
+            .expect("loaded")
+            .starts_with("d2025.bin")
+    );
+
+    let found = repo
+        .find_by_term("intracranial  ANEURYSM")
+        .expect("lookup")
+        .expect("found");
+    assert_eq!(found.ui, "D002532");
+    let inverted = repo
+        .find_by_term("aneurysm, intracranial")
+        .expect("lookup")
+        .expect("found");
+    assert_eq!(inverted.ui, "D002532");
+
+    let query = parse_query("aneurysm clipping -coiling").expect("parse");
+    let (expanded, terms) = expand(&query, &repo).expect("expand");
+    assert_eq!(terms.len(), 1);
+    assert_eq!(terms[0].descriptor, "Aneurysm");
+    assert_eq!(terms[0].entry_terms, vec!["Aneurysms"]);
+    assert_eq!(terms[0].narrower, vec!["Intracranial Aneurysm"]);
+    assert_eq!(
+        expanded.to_string(),
+        r#"(aneurysm OR mesh:Aneurysm OR Aneurysms OR "Intracranial Aneurysm") AND clipping AND NOT coiling"#
+    );
+
+    // Adjacent words are matched as one heading before falling back to single words.
+    let (expanded, terms) =
+        expand(&parse_query("intracranial aneurysm").expect("parse"), &repo).expect("expand");
+    assert_eq!(terms[0].descriptor_ui, "D002532");
+    assert_eq!(
+        expanded.to_string(),
+        r#"(intracranial AND aneurysm) OR mesh:"Intracranial Aneurysm" OR "Cerebral Aneurysm""#
+    );
+}
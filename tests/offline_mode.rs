@@ -0,0 +1,112 @@
+use chrono::NaiveDate;
+use lit::cache::http_cache::HttpCache;
+use lit::cli::SearchArgs;
+use lit::config::AppConfig;
+use lit::connectors::ApiClient;
+use lit::errors::LitError;
+use lit::pipeline::app::App;
+use lit::pipeline::search;
+use lit::types::{SearchOutput, SourceState};
+use serde_json::json;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn offline_serves_stale_cache_and_reports_misses() {
+    let dir = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(dir.path())).expect("config");
+    cfg.network.offline = true;
+    // Everything is stale immediately; offline must serve it anyway.
+    cfg.cache.default_ttl_secs = 0;
+    cfg.cache.ttl_secs.clear();
+    let api = ApiClient::new(&cfg).expect("client");
+    assert!(api.is_offline());
+
+    let url = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi?term=x";
+    let key = HttpCache::key_for("pubmed", url);
+    api.cache()
+        .put("pubmed", url, &key, "{\"ok\":true}", None, None)
+        .expect("put");
+    assert!(
+        !api.cache()
+            .get("pubmed", &key)
+            .expect("get")
+            .expect("hit")
+            .fresh
+    );
+    assert_eq!(
+        api.get_text_cached("pubmed", url)
+            .await
+            .expect("cached body"),
+        "{\"ok\":true}"
+    );
+
+    let missing = "https://api.crossref.org/works?query=y";
+    let err = api
+        .get_text_cached("crossref", missing)
+        .await
+        .expect_err("miss");
+    assert!(matches!(err, LitError::CacheMiss(..)));
+    assert_eq!(
+        err.to_string(),
+        format!("cache miss for crossref {missing}")
+    );
+
+    assert!(matches!(
+        api.download_bytes("unpaywall", "https://example.org/a.pdf")
+            .await,
+        Err(LitError::Offline(_))
+    ));
+}
+
+#[tokio::test]
+async fn offline_search_resolves_relative_windows_on_the_cached_day() {
+    let dir = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(dir.path())).expect("config");
+    cfg.network.offline = true;
+    let app = App::new(cfg).expect("app");
+
+    // A `--since 30d` search that ran online on 2024-06-30 left its page in the cache.
+    let url = "https://api.crossref.org/works?query.bibliographic=tranexamic&rows=20\
+               &cursor=%2A&filter=from-pub-date:2024-05-31";
+    let body = json!({"message": {"items": [{"DOI": "10.1000/txa", "title": ["TXA"]}]}});
+    app.api
+        .cache()
+        .put("crossref", url, &HttpCache::key_for("crossref", url), &body.to_string(), None, None)
+        .expect("put");
+    let out = app.paths.search_output_path();
+    std::fs::create_dir_all(out.parent().expect("artifacts dir")).expect("mkdir");
+    std::fs::write(
+        &out,
+        json!({
+            "query": "tranexamic",
+            "since": {"raw": "30d", "from": "2024-05-31", "to": null},
+            "generated_at": "2024-06-30T09:00:00Z",
+            "results": []
+        })
+        .to_string(),
+    )
+    .expect("previous output");
+
+    // Twice: the second run must pin to the day the first one recorded, not its own.
+    for _ in 0..2 {
+        search::run(
+            &app,
+            SearchArgs {
+                query: "tranexamic".into(),
+                since: Some("30d".parse().expect("window")),
+                limit: 20,
+                sources: vec!["crossref".into()],
+                out: None,
+                explain: false,
+                expand_mesh: false,
+            },
+        )
+        .await
+        .expect("offline search");
+        let output: SearchOutput =
+            serde_json::from_str(&std::fs::read_to_string(&out).expect("output")).expect("json");
+        assert_eq!(output.source_status[0].status, SourceState::Ok);
+        assert_eq!(output.results.len(), 1);
+        assert_eq!(output.resolved_on, NaiveDate::from_ymd_opt(2024, 6, 30));
+    }
+}
@@ -0,0 +1,95 @@
+use lit::config::AppConfig;
+use lit::connectors::{ApiClient, pubmed};
+use lit::query::window::DateWindow;
+use tempfile::tempdir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn history_search_is_refetched_and_esummary_errors_are_reported() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/esearch.fcgi"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "esearchresult": {"count": "250", "webenv": "MCID_1", "querykey": "1", "idlist": []}
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/esummary.fcgi"))
+        .and(query_param("retstart", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": {"uids": ["1"], "1": {"title": "First", "pubdate": "2024"}}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/esummary.fcgi"))
+        .and(query_param("retstart", "200"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"error": "Unable to obtain query #1"})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/efetch.fcgi"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<PubmedArticleSet/>"))
+        .mount(&server)
+        .await;
+
+    let data = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(data.path())).expect("config");
+    cfg.retry.max_attempts = 1;
+    cfg.endpoints.pubmed = server.uri();
+    let client = ApiClient::new(&cfg).expect("client");
+    let window = DateWindow::parse("all").expect("window");
+
+    for _ in 0..2 {
+        let batch = pubmed::search(&client, "aneurysm", 250, &window, "")
+            .await
+            .expect("search");
+        assert_eq!(batch.results.len(), 1);
+        let err = batch.partial_error.expect("partial");
+        assert!(err.contains("Unable to obtain query"), "{err}");
+    }
+}
+
+#[tokio::test]
+async fn efetch_failures_are_warnings_not_partial_results() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/esearch.fcgi"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "esearchresult": {"count": "1", "webenv": "MCID_2", "querykey": "1", "idlist": []}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/esummary.fcgi"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": {"uids": ["1"], "1": {"title": "First", "pubdate": "2024"}}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/efetch.fcgi"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let data = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(data.path())).expect("config");
+    cfg.retry.max_attempts = 1;
+    cfg.endpoints.pubmed = server.uri();
+    let client = ApiClient::new(&cfg).expect("client");
+    let window = DateWindow::parse("all").expect("window");
+
+    let batch = pubmed::search(&client, "aneurysm", 10, &window, "")
+        .await
+        .expect("search");
+    assert_eq!(batch.results.len(), 1);
+    assert!(batch.partial_error.is_none());
+    assert_eq!(batch.warnings.len(), 1);
+}
@@ -0,0 +1,68 @@
+use lit::config::{AppConfig, RetryOverride};
+use lit::connectors::ApiClient;
+use lit::errors::LitError;
+use tempfile::tempdir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn only_transient_statuses_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/busy"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/busy"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/down"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let data = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(data.path())).expect("config");
+    cfg.retry.max_attempts = 5;
+    cfg.retry.base_delay_ms = 1;
+    cfg.retry.max_delay_ms = 5;
+    cfg.retry.sources.insert(
+        "clinicaltrials".to_string(),
+        RetryOverride {
+            max_attempts: Some(2),
+            ..RetryOverride::default()
+        },
+    );
+    let client = ApiClient::new(&cfg).expect("client");
+
+    let missing = client
+        .get_text_cached("crossref", &format!("{}/missing", server.uri()))
+        .await
+        .expect_err("404");
+    assert!(matches!(&missing, LitError::HttpStatus(e) if e.status == 404));
+
+    let busy = client
+        .get_text_cached("crossref", &format!("{}/busy", server.uri()))
+        .await
+        .expect("retried after 429");
+    assert_eq!(busy, "ok");
+
+    let down = client
+        .download_bytes("clinicaltrials", &format!("{}/down", server.uri()))
+        .await
+        .expect_err("503");
+    assert!(matches!(&down, LitError::HttpStatus(e) if e.status == 503));
+}
@@ -23,6 +23,7 @@ fn evidence_ledger_schema_validates_required_shape() {
         anchor_type: AnchorType::Pdf,
         page_number: Some(3),
         section_heading: None,
+        registry: None,
         anchor_quote: "Outcome improved at 12 months with 30% reduction".into(),
         claim_text: "Outcome improved at 12 months with 30% reduction".into(),
         claim_type: ClaimType::Outcome,
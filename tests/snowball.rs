@@ -0,0 +1,97 @@
+use lit::cli::{SnowballArgs, SnowballDirection};
+use lit::config::AppConfig;
+use lit::pipeline::app::App;
+use lit::pipeline::snowball;
+use lit::types::SearchResult;
+use serde_json::{Value, json};
+use tempfile::tempdir;
+use wiremock::matchers::{method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn work(id: &str, refs: &[&str]) -> Value {
+    json!({
+        "id": format!("https://openalex.org/{id}"),
+        "doi": format!("https://doi.org/10.1/{}", id.to_lowercase()),
+        "display_name": format!("Work {id}"),
+        "publication_year": 2020,
+        "referenced_works": refs
+            .iter()
+            .map(|r| format!("https://openalex.org/{r}"))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[tokio::test]
+async fn snowball_follows_references_from_the_pmid_fallback_and_stops_at_max_works() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path_regex("^/works/doi"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex("^/works/pmid"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work("W1", &["W2", "W3"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/works"))
+        .and(query_param("filter", "openalex:W2|W3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [work("W2", &["W4", "W5"]), work("W3", &[])]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/works"))
+        .and(query_param("filter", "openalex:W4"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [work("W4", &["W6"])]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = tempdir().expect("tempdir");
+    let mut cfg = AppConfig::load(None, Some(data.path())).expect("config");
+    cfg.retry.max_attempts = 1;
+    cfg.endpoints.openalex = server.uri();
+    let app = App::new(cfg).expect("app");
+    let seed = app
+        .docs
+        .upsert_from_search(&SearchResult {
+            source: "pubmed".into(),
+            doi: Some("10.1/unindexed".into()),
+            pmid: Some("11".into()),
+            title: "Seed study".into(),
+            year: Some(2019),
+            ..Default::default()
+        })
+        .expect("seed");
+
+    snowball::run(
+        &app,
+        SnowballArgs {
+            doc_ids: seed.doc_id.clone(),
+            direction: SnowballDirection::Back,
+            depth: 3,
+            max_citing: 200,
+            max_works: 3,
+        },
+    )
+    .await
+    .expect("snowball");
+
+    let docs = app.docs.list_docs().expect("docs");
+    let mut titles = docs.iter().map(|d| d.title.as_str()).collect::<Vec<_>>();
+    titles.sort();
+    assert_eq!(titles, vec!["Seed study", "Work W2", "Work W3", "Work W4"]);
+    let tagged = docs.iter().find(|d| d.title == "Work W4").expect("W4");
+    assert_eq!(
+        app.docs.provenance(&tagged.doc_id).expect("provenance"),
+        vec![format!("snowball from {}", seed.doc_id)]
+    );
+}
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use lit::db::trials_repo::TrialsRepo;
+use lit::db::Db;
+use lit::domain::trial::{TrialIntervention, TrialOutcome, TrialRecord};
+use lit::pipeline::synthesis::brief_trials;
+use std::collections::HashSet;
+use tempfile::tempdir;
+
+fn trial(status: &str, doc_id: Option<&str>) -> TrialRecord {
+    TrialRecord {
+        nct_id: "NCT01234567".into(),
+        doc_id: doc_id.map(ToString::to_string),
+        title: "TXA for cSDH".into(),
+        official_title: None,
+        phases: vec!["PHASE3".into()],
+        overall_status: Some(status.into()),
+        start_date: Some("2019-03".into()),
+        completion_date: None,
+        enrollment: Some(240),
+        enrollment_type: Some("ACTUAL".into()),
+        conditions: vec!["Chronic Subdural Hematoma".into()],
+        interventions: vec![TrialIntervention {
+            kind: Some("DRUG".into()),
+            name: "Tranexamic acid".into(),
+        }],
+        primary_outcomes: vec![TrialOutcome {
+            measure: "Recurrence".into(),
+            time_frame: Some("6 months".into()),
+            description: None,
+        }],
+        secondary_outcomes: vec![],
+        sponsor: Some("Example University".into()),
+        has_results: false,
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn trial_upsert_round_trips_and_refreshes_status() {
+    let dir = tempdir().expect("tempdir");
+    let db = Db::open(&dir.path().join("lit.db")).expect("db open");
+    let repo = TrialsRepo::new(db);
+
+    repo.upsert(&trial("RECRUITING", Some("doc_1"))).expect("insert");
+    repo.upsert(&trial("COMPLETED", None)).expect("update");
+
+    let stored = repo.get("nct01234567").expect("get").expect("trial stored");
+    assert_eq!(stored.overall_status.as_deref(), Some("COMPLETED"));
+    assert_eq!(stored.doc_id.as_deref(), Some("doc_1"));
+    assert_eq!(stored.interventions[0].name, "Tranexamic acid");
+    assert_eq!(stored.primary_outcomes[0].measure, "Recurrence");
+    assert_eq!(repo.list().expect("list").len(), 1);
+    assert!(repo.for_doc("doc_1").expect("for_doc").is_some());
+}
+
+#[test]
+fn doc_links_skip_the_registry_doc_and_dedupe_per_source() {
+    let dir = tempdir().expect("tempdir");
+    let db = Db::open(&dir.path().join("lit.db")).expect("db open");
+    let repo = TrialsRepo::new(db);
+    repo.upsert(&trial("COMPLETED", Some("doc_registry"))).expect("insert");
+
+    repo.link_doc("doc_paper", "nct01234567", "xml").expect("link");
+    repo.link_doc("doc_paper", "NCT01234567", "xml").expect("relink");
+    repo.link_doc("doc_paper", "NCT01234567", "pubmed_databank").expect("link databank");
+    repo.link_doc("doc_registry", "NCT01234567", "xml").expect("self link");
+
+    let for_trial = repo.links_for_trial("NCT01234567").expect("links");
+    assert_eq!(for_trial.len(), 2);
+    assert!(for_trial.iter().all(|l| l.doc_id == "doc_paper"));
+    let reports = for_trial.iter().filter(|l| l.reports()).collect::<Vec<_>>();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].source, "pubmed_databank");
+    assert_eq!(repo.links_for_doc("doc_paper").expect("doc links").len(), 2);
+    assert!(repo.links_for_trial("NCT07654321").expect("none").is_empty());
+}
+
+#[test]
+fn brief_trials_skip_trials_unrelated_to_citations_or_query() {
+    let dir = tempdir().expect("tempdir");
+    let db = Db::open(&dir.path().join("lit.db")).expect("db open");
+    let repo = TrialsRepo::new(db);
+    repo.upsert(&trial("COMPLETED", None)).expect("linked trial");
+    repo.upsert(&TrialRecord {
+        nct_id: "NCT05555555".into(),
+        title: "Embolization of the middle meningeal artery".into(),
+        conditions: vec!["Subdural Hematoma".into()],
+        interventions: vec![],
+        ..trial("RECRUITING", None)
+    })
+    .expect("query match");
+    repo.upsert(&TrialRecord {
+        nct_id: "NCT09999999".into(),
+        title: "Statins in heart failure".into(),
+        conditions: vec!["Heart Failure".into()],
+        interventions: vec![],
+        ..trial("COMPLETED", None)
+    })
+    .expect("unrelated");
+    repo.link_doc("doc_cited", "NCT01234567", "xml").expect("link");
+
+    let cited = HashSet::from(["doc_cited".to_string()]);
+    let selected = brief_trials(&repo, &cited, "embolization", 20).expect("select");
+    let ids = selected.iter().map(|t| t.nct_id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["NCT01234567", "NCT05555555"]);
+}